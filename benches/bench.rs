@@ -1,3 +1,5 @@
+#![allow(clippy::useless_concat)]
+
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 
 fn call() -> impl IntoIterator<Item = &'static str> {
@@ -9,6 +11,15 @@ fn call() -> impl IntoIterator<Item = &'static str> {
     ]
 }
 
+fn call_static() -> impl IntoIterator<Item = &'static str> {
+    [
+        "/user/repos",
+        "/search/repositories",
+        "/gitignore/templates",
+        "/notifications",
+    ]
+}
+
 fn compare_routers(c: &mut Criterion) {
     let mut group = c.benchmark_group("Compare Routers");
 
@@ -24,6 +35,33 @@ fn compare_routers(c: &mut Criterion) {
         });
     });
 
+    let frozen = matchit.clone().freeze();
+    group.bench_function("matchit (frozen)", |b| {
+        b.iter(|| {
+            for route in black_box(call()) {
+                black_box(frozen.at(route).unwrap());
+            }
+        });
+    });
+
+    // routes without parameters are looked up in an index before walking the tree,
+    // except by the frozen router
+    group.bench_function("matchit (static)", |b| {
+        b.iter(|| {
+            for route in black_box(call_static()) {
+                black_box(matchit.at(route).unwrap());
+            }
+        });
+    });
+
+    group.bench_function("matchit (frozen, static)", |b| {
+        b.iter(|| {
+            for route in black_box(call_static()) {
+                black_box(frozen.at(route).unwrap());
+            }
+        });
+    });
+
     let mut path_tree = path_tree::PathTree::new();
     for route in register!(colon) {
         path_tree.insert(route, true);
@@ -95,7 +133,110 @@ fn compare_routers(c: &mut Criterion) {
     group.finish();
 }
 
-criterion_group!(benches, compare_routers);
+fn insert(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Insert");
+
+    group.bench_function("matchit", |b| {
+        b.iter(|| {
+            let mut matchit = matchit::Router::new();
+            for route in black_box(register!(colon)) {
+                matchit.insert(route, true).unwrap();
+            }
+            matchit
+        });
+    });
+
+    group.finish();
+}
+
+fn long_paths(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Long Paths");
+
+    let mut matchit = matchit::Router::new();
+
+    // a deep tree, with a static and a parameter segment at every level
+    let mut route = String::new();
+    for i in 0..16 {
+        route.push_str(&format!("/segment_{}/:p{}", i, i));
+        matchit.insert(route.clone(), true).unwrap();
+        matchit
+            .insert(format!("{}/static_{}", route, i), true)
+            .unwrap();
+    }
+    matchit.insert("/files/:name/*rest", true).unwrap();
+
+    let deep = (0..16)
+        .map(|i| format!("/segment_{}/value_{}", i, i))
+        .collect::<String>();
+    group.bench_function("deep tree", |b| {
+        b.iter(|| black_box(matchit.at(black_box(&deep)).unwrap()));
+    });
+
+    let segment = "x".repeat(256);
+    let long = format!("/files/{}/{}/{}", segment, segment, segment);
+    group.bench_function("long segments", |b| {
+        b.iter(|| black_box(matchit.at(black_box(&long)).unwrap()));
+    });
+
+    group.finish();
+}
+
+fn params_access(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Params Access");
+
+    let mut matchit = matchit::Router::new();
+    for route in register!(colon) {
+        matchit.insert(route, true).unwrap();
+    }
+    matchit.insert("/files/:owner/*path", true).unwrap();
+
+    let segment = "x".repeat(64);
+    let path = format!("/files/{}/{}/{}/{}", segment, segment, segment, segment);
+    let paths = [
+        "/repos/rust-lang/rust/stargazers",
+        "/orgs/rust-lang/public_members/nikomatsakis",
+        "/repos/rust-lang/rust/releases/1.51.0",
+        &path,
+    ];
+
+    // parameters matched against strings are valid UTF-8 by construction, so they are
+    // never checked
+    let matched = paths.map(|path| matchit.at(path).unwrap().params);
+    group.bench_function("iter", |b| {
+        b.iter(|| {
+            for params in black_box(&matched) {
+                for param in params.iter() {
+                    black_box(param);
+                }
+            }
+        });
+    });
+
+    // parameters matched against bytes are only accessible as bytes, so they aren't
+    // checked either
+    let matched_bytes = paths.map(|path| matchit.at_bytes(path.as_bytes()).unwrap().params);
+    group.bench_function("iter (bytes)", |b| {
+        b.iter(|| {
+            for params in black_box(&matched_bytes) {
+                for param in params.iter() {
+                    black_box(param);
+                }
+            }
+        });
+    });
+
+    group.bench_function("get", |b| {
+        b.iter(|| {
+            for params in black_box(&matched) {
+                black_box(params.get(black_box("path")));
+            }
+        });
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, compare_routers, insert, long_paths, params_access);
 criterion_main!(benches);
 
 macro_rules! register {
@@ -0,0 +1,78 @@
+use std::convert::Infallible;
+use std::future::Future;
+use std::pin::Pin;
+
+use hyper::server::Server;
+use hyper::service::make_service_fn;
+use hyper::{Body, Request, Response};
+use matchit::{MatchError, OwnedParams, Router, RouterService};
+use tower::util::ServiceFn;
+
+// A handler is a plain function returning a boxed future. Function pointers are `Sync`,
+// so the router can be shared between connections without a `Mutex`.
+type BoxFuture = Pin<Box<dyn Future<Output = hyper::Result<Response<Body>>> + Send>>;
+type Handler = ServiceFn<fn(Request<Body>) -> BoxFuture>;
+
+fn handler(f: fn(Request<Body>) -> BoxFuture) -> Handler {
+    tower::service_fn(f)
+}
+
+// GET /
+fn index(_req: Request<Body>) -> BoxFuture {
+    Box::pin(async { Ok(Response::new(Body::from("Hello, world!"))) })
+}
+
+// GET /users/:id
+fn user(req: Request<Body>) -> BoxFuture {
+    // the parameters of the matched route are stored in the request extensions
+    let params = req.extensions().get::<OwnedParams>().unwrap();
+    let body = format!("User #{}", params.get("id").unwrap());
+
+    Box::pin(async { Ok(Response::new(Body::from(body))) })
+}
+
+// 404 handler
+fn not_found(_req: Request<Body>) -> BoxFuture {
+    Box::pin(async { Ok(Response::builder().status(404).body(Body::empty()).unwrap()) })
+}
+
+// redirect paths with a missing or extra trailing slash
+fn trailing_slash(req: Request<Body>) -> BoxFuture {
+    let path = req.uri().path();
+
+    let location = match req.extensions().get::<MatchError>() {
+        Some(MatchError::MissingTrailingSlash) => format!("{}/", path),
+        _ => path[..path.len() - 1].to_owned(),
+    };
+
+    Box::pin(async move {
+        Ok(Response::builder()
+            .status(308)
+            .header("location", location)
+            .body(Body::empty())
+            .unwrap())
+    })
+}
+
+#[tokio::main]
+async fn main() {
+    // Create a router and register our routes.
+    let mut router = Router::new();
+    router.insert("/", handler(index)).unwrap();
+    router.insert("/users/:id", handler(user)).unwrap();
+
+    let service = RouterService::new(router, handler(not_found))
+        .trailing_slash_fallback(handler(trailing_slash));
+
+    // every connection gets a cheap clone of the service
+    let make_service = make_service_fn(move |_| {
+        let service = service.clone();
+        async { Ok::<_, Infallible>(service) }
+    });
+
+    // run the server
+    Server::bind(&([127, 0, 0, 1], 3000).into())
+        .serve(make_service)
+        .await
+        .unwrap()
+}
@@ -0,0 +1,136 @@
+//! Compile-time route tables for [`matchit`](https://docs.rs/matchit).
+//!
+//! The [`router!`] macro builds a [`StaticRouter`](matchit::StaticRouter) from a list of
+//! routes, validating them and checking for conflicts at compile time.
+//!
+//! ```rust
+//! use matchit_macros::router;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let router = router! {
+//!     "/home" => "Welcome!",
+//!     "/users/:id" => "A User",
+//! };
+//!
+//! let matched = router.at("/users/978")?;
+//! assert_eq!(matched.params.get("id"), Some("978"));
+//! assert_eq!(*matched.value, "A User");
+//! # Ok(())
+//! # }
+//! ```
+#![deny(rust_2018_idioms, clippy::all)]
+
+use matchit::Router;
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{parse_macro_input, Expr, LitStr, Token};
+
+/// Creates a [`StaticRouter`](matchit::StaticRouter) from a list of routes and values.
+///
+/// The routes are inserted in order, exactly like with [`Router::insert`](matchit::Router::insert).
+/// Invalid routes and conflicts between routes are reported as compile errors, and the
+/// resulting tree is laid out in static memory. All values must have the same type.
+///
+/// Because the router is created with a `const fn`, it can also be used to initialize
+/// a `static` if the values are constant.
+///
+/// ```rust
+/// use matchit::StaticRouter;
+/// use matchit_macros::router;
+///
+/// static ROUTER: StaticRouter<u32, 2> = router! {
+///     "/users/:id" => 1,
+///     "/users/:id/posts" => 2,
+/// };
+///
+/// assert_eq!(ROUTER.at("/users/1/posts").map(|m| *m.value), Ok(2));
+/// ```
+///
+/// Conflicting routes fail to compile:
+///
+/// ```rust,compile_fail
+/// let router = matchit_macros::router! {
+///     "/users/:id" => 1,
+///     "/users/:user_id" => 2,
+/// };
+/// ```
+///
+/// As do invalid ones:
+///
+/// ```rust,compile_fail
+/// let router = matchit_macros::router! {
+///     "/files/*path/edit" => 1,
+/// };
+/// ```
+#[proc_macro]
+pub fn router(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let Routes(routes) = parse_macro_input!(input as Routes);
+
+    match expand(&routes) {
+        Ok(expanded) => expanded.into(),
+        Err(error) => {
+            // wrap the errors in a block so that there can be more than one
+            let error = error.to_compile_error();
+            quote!({ #error }).into()
+        }
+    }
+}
+
+// A list of `"route" => value` pairs.
+struct Routes(Punctuated<Route, Token![,]>);
+
+struct Route {
+    route: LitStr,
+    value: Expr,
+}
+
+impl Parse for Routes {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        Punctuated::parse_terminated(input).map(Routes)
+    }
+}
+
+impl Parse for Route {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let route = input.parse()?;
+        input.parse::<Token![=>]>()?;
+        let value = input.parse()?;
+        Ok(Route { route, value })
+    }
+}
+
+fn expand(routes: &Punctuated<Route, Token![,]>) -> syn::Result<TokenStream> {
+    // insert the routes into a router at compile time, with the index of each route as its
+    // value, to report the same errors as inserting them at runtime
+    let mut router = Router::new();
+    let mut errors: Option<syn::Error> = None;
+
+    for (i, Route { route, .. }) in routes.iter().enumerate() {
+        if let Err(err) = router.insert(route.value(), i) {
+            let err = syn::Error::new(route.span(), format!("invalid route: {}", err));
+
+            match &mut errors {
+                Some(errors) => errors.combine(err),
+                None => errors = Some(err),
+            }
+        }
+    }
+
+    if let Some(errors) = errors {
+        return Err(errors);
+    }
+
+    let root: TokenStream = router
+        .to_static_table()
+        .parse()
+        .expect("generated table is valid Rust");
+    let values = routes.iter().map(|route| &route.value);
+
+    Ok(quote! {{
+        static ROOT: ::matchit::StaticNode = #root;
+        ::matchit::StaticRouter::from_table(&ROOT, [#(#values),*])
+    }})
+}
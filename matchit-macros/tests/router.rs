@@ -0,0 +1,243 @@
+use matchit::Router;
+use matchit_macros::router;
+
+// Checks that the static router matches every path exactly like a router built at runtime.
+macro_rules! assert_same {
+    ($($route:literal),* $(,)?; $($path:literal),* $(,)?) => {{
+        let router = router! { $($route => $route),* };
+
+        let mut runtime = Router::new();
+        $(runtime.insert($route, $route).unwrap();)*
+
+        $(
+            assert_eq!(
+                router.at($path).map(|m| (*m.value, m.params.iter().collect::<Vec<_>>())),
+                runtime.at($path).map(|m| (*m.value, m.params.iter().collect::<Vec<_>>())),
+                "static router differs for '{}'",
+                $path
+            );
+        )*
+    }};
+}
+
+#[test]
+fn wildcard() {
+    assert_same!(
+        "/",
+        "/cmd/:tool/",
+        "/cmd/:tool2/:sub",
+        "/cmd/whoami",
+        "/cmd/whoami/root",
+        "/cmd/whoami/root/",
+        "/src",
+        "/src/",
+        "/src/*filepath",
+        "/search/",
+        "/search/:query",
+        "/search/actix-web",
+        "/search/google",
+        "/user_:name",
+        "/user_:name/about",
+        "/files/:dir/*filepath",
+        "/doc/",
+        "/doc/rust_faq.html",
+        "/doc/rust1.26.html",
+        "/info/:user/public",
+        "/info/:user/project/:project",
+        "/info/:user/project/rustlang",
+        "/aa/*xx",
+        "/ab/*xx",
+        "/ab/hello*xx",
+        "/:cc",
+        "/c1/:dd/e",
+        "/c1/:dd/e1",
+        "/:cc/cc",
+        "/:cc/:dd/ee",
+        "/:cc/:dd/:ee/ff",
+        "/:cc/:dd/:ee/:ff/gg",
+        "/:cc/:dd/:ee/:ff/:gg/hh",
+        "/get/test/abc/",
+        "/get/:param/abc/",
+        "/something/:paramname/thirdthing",
+        "/something/secondthing/test",
+        "/get/abc",
+        "/get/:param",
+        "/get/abc/123abc",
+        "/get/abc/:param",
+        "/get/abc/123abc/xxx8",
+        "/get/abc/123abc/:param",
+        "/get/abc/123abc/xxx8/1234",
+        "/get/abc/123abc/xxx8/:param",
+        "/get/abc/123abc/xxx8/1234/ffas",
+        "/get/abc/123abc/xxx8/1234/:param",
+        "/get/abc/123abc/xxx8/1234/kkdd/12c",
+        "/get/abc/123abc/xxx8/1234/kkdd/:param",
+        "/get/abc/:param/test",
+        "/get/abc/123abd/:param",
+        "/get/abc/123abddd/:param",
+        "/get/abc/123/:param",
+        "/get/abc/123abg/:param",
+        "/get/abc/123abf/:param",
+        "/get/abc/123abfff/:param",
+        ;
+        "/",
+        "/cmd/test",
+        "/cmd/test/",
+        "/cmd/test/3",
+        "/cmd/who",
+        "/cmd/who/",
+        "/cmd/whoami",
+        "/cmd/whoami/",
+        "/cmd/whoami/r",
+        "/cmd/whoami/r/",
+        "/cmd/whoami/root",
+        "/cmd/whoami/root/",
+        "/src",
+        "/src/",
+        "/src/some/file.png",
+        "/search/",
+        "/search/actix",
+        "/search/actix-web",
+        "/search/someth!ng+in+ünìcodé",
+        "/search/someth!ng+in+ünìcodé/",
+        "/user_rustacean",
+        "/user_rustacean/about",
+        "/files/js/inc/framework.js",
+        "/info/gordon/public",
+        "/info/gordon/project/rust",
+        "/info/gordon/project/rustlang",
+        "/aa/",
+        "/aa/aa",
+        "/ab/ab",
+        "/ab/hello-world",
+        "/a",
+        "/all",
+        "/d",
+        "/ad",
+        "/dd",
+        "/dddaa",
+        "/aa",
+        "/aaa",
+        "/aaa/cc",
+        "/ab",
+        "/abb",
+        "/abb/cc",
+        "/allxxxx",
+        "/alldd",
+        "/all/cc",
+        "/a/cc",
+        "/c1/d/e",
+        "/c1/d/e1",
+        "/c1/d/ee",
+        "/cc/cc",
+        "/ccc/cc",
+        "/deedwjfs/cc",
+        "/acllcc/cc",
+        "/get/test/abc/",
+        "/get/te/abc/",
+        "/get/testaa/abc/",
+        "/get/xx/abc/",
+        "/get/tt/abc/",
+        "/get/a/abc/",
+        "/get/t/abc/",
+        "/get/aa/abc/",
+        "/get/abas/abc/",
+        "/something/secondthing/test",
+        "/something/abcdad/thirdthing",
+        "/something/secondthingaaaa/thirdthing",
+        "/something/se/thirdthing",
+        "/something/s/thirdthing",
+        "/c/d/ee",
+        "/c/d/e/ff",
+        "/c/d/e/f/gg",
+        "/c/d/e/f/g/hh",
+        "/cc/dd/ee/ff/gg/hh",
+        "/get/abc",
+        "/get/a",
+        "/get/abz",
+        "/get/12a",
+        "/get/abcd",
+        "/get/abc/123abc",
+        "/get/abc/12",
+        "/get/abc/123ab",
+        "/get/abc/xyz",
+        "/get/abc/123abcddxx",
+        "/get/abc/123abc/xxx8",
+        "/get/abc/123abc/x",
+        "/get/abc/123abc/xxx",
+        "/get/abc/123abc/abc",
+        "/get/abc/123abc/xxx8xxas",
+        "/get/abc/123abc/xxx8/1234",
+        "/get/abc/123abc/xxx8/1",
+        "/get/abc/123abc/xxx8/123",
+        "/get/abc/123abc/xxx8/78k",
+        "/get/abc/123abc/xxx8/1234xxxd",
+        "/get/abc/123abc/xxx8/1234/ffas",
+        "/get/abc/123abc/xxx8/1234/f",
+        "/get/abc/123abc/xxx8/1234/ffa",
+        "/get/abc/123abc/xxx8/1234/kka",
+        "/get/abc/123abc/xxx8/1234/ffas321",
+        "/get/abc/123abc/xxx8/1234/kkdd/12c",
+        "/get/abc/123abc/xxx8/1234/kkdd/1",
+        "/get/abc/123abc/xxx8/1234/kkdd/12",
+        "/get/abc/123abc/xxx8/1234/kkdd/12b",
+        "/get/abc/123abc/xxx8/1234/kkdd/34",
+        "/get/abc/123abc/xxx8/1234/kkdd/12c2e3",
+        "/get/abc/12/test",
+        "/get/abc/123abdd/test",
+        "/get/abc/123abdddf/test",
+        "/get/abc/123ab/test",
+        "/get/abc/123abgg/test",
+        "/get/abc/123abff/test",
+        "/get/abc/123abffff/test",
+        "/get/abc/123abd/test",
+        "/get/abc/123abddd/test",
+        "/get/abc/123/test22",
+        "/get/abc/123abg/test",
+        "/get/abc/123abf/testss",
+        "/get/abc/123abfff/te",
+        "/cmd/whoami/root/x",
+        "/get/abc/123abc/xxx8/1234/kkdd/12c/",
+        "/src",
+        "/src/",
+        "/aa",
+        "/ab/",
+        "/user_",
+        "/info/gordon/project/",
+        "",
+    );
+}
+
+#[test]
+fn params() {
+    assert_same!(
+        "/users/:id",
+        "/users/:user_id/posts/:post_id",
+        "/users/new",
+        "/files/*path",
+        "/:a/:b/:c/:d",
+        ;
+        "/users/1",
+        "/users/1/posts/2",
+        "/users/new",
+        "/users/new/",
+        "/files/a/b/c",
+        "/files/",
+        "/1/2/3/4",
+        "/1/2/3",
+    );
+}
+
+#[test]
+fn at_mut() {
+    let mut router = router! {
+        "/counter" => 0,
+        "/counter/:id" => 10,
+    };
+
+    *router.at_mut("/counter").unwrap().value += 1;
+    *router.at_mut("/counter/1").unwrap().value += 1;
+
+    assert_eq!(router.at("/counter").map(|m| *m.value), Ok(1));
+    assert_eq!(router.at("/counter/2").map(|m| *m.value), Ok(11));
+}
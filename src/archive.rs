@@ -0,0 +1,195 @@
+//! Zero-copy archives of a router, behind the `rkyv` feature.
+//!
+//! Archives are read in place, so unlike a deserialized router, the tree can't be checked
+//! as it is built. Instead, every invariant that insertion upholds is checked when an
+//! archive is accessed, which matching relies on for memory safety.
+
+use crate::tree::{self, ArchivedNodeType, Lookup, Node, NodeType, Untrusted};
+use crate::{Match, MatchError, Router};
+use alloc::vec::Vec;
+
+use core::fmt;
+
+use rkyv::bytecheck::Verify;
+use rkyv::rancor::{Fallible, Source};
+use rkyv::{Archive, Archived, Serialize};
+
+/// A router in a form that can be archived with [`rkyv`], created with [`Router::into_archivable`].
+///
+/// The archived form, [`ArchivedRouter`], can be matched against directly, for example from
+/// a memory-mapped file, without deserializing it first.
+///
+/// ```rust
+/// use matchit::{ArchivedRouter, Router};
+/// use rkyv::rancor::Error;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut router = Router::new();
+/// router.insert("/home", "Welcome!".to_owned())?;
+/// router.insert("/users/:id", "A User".to_owned())?;
+///
+/// let bytes = rkyv::to_bytes::<Error>(&router.into_archivable())?;
+///
+/// let archived = rkyv::access::<ArchivedRouter<String>, Error>(&bytes)?;
+/// let matched = archived.at("/users/978")?;
+/// assert_eq!(matched.params.get("id"), Some("978"));
+/// assert_eq!(matched.value, "A User");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Archive, Serialize)]
+#[rkyv(archived = ArchivedRouter)]
+#[rkyv(bytecheck(
+    verify,
+    bounds(
+        __C: rkyv::validation::ArchiveContext,
+        __C::Error: rkyv::rancor::Source,
+    )
+))]
+pub struct ArchivableRouter<T> {
+    root: ArchivableNode<T>,
+}
+
+// A node of an archivable router.
+#[derive(Archive, Serialize)]
+#[rkyv(archived = ArchivedNode)]
+#[rkyv(serialize_bounds(
+    __S: rkyv::ser::Writer + rkyv::ser::Allocator,
+    __S::Error: rkyv::rancor::Source,
+))]
+#[rkyv(bytecheck(bounds(
+    __C: rkyv::validation::ArchiveContext,
+    __C::Error: rkyv::rancor::Source,
+)))]
+struct ArchivableNode<T> {
+    prefix: Vec<u8>,
+    indices: Vec<u8>,
+    node_type: NodeType,
+    wild_child: bool,
+    value: Option<T>,
+    param_remapping: Vec<Vec<u8>>,
+    #[rkyv(omit_bounds)]
+    children: Vec<ArchivableNode<T>>,
+}
+
+impl<T> ArchivableNode<T> {
+    fn new(node: Node<T>) -> Self {
+        ArchivableNode {
+            prefix: node.prefix,
+            indices: node.indices,
+            node_type: node.node_type,
+            wild_child: node.wild_child,
+            value: node.value.map(|value| value.into_inner().0),
+            param_remapping: node.param_remapping,
+            children: node.children.into_iter().map(Self::new).collect(),
+        }
+    }
+}
+
+impl<T> Router<T> {
+    /// Converts the router into a form that can be archived with [`rkyv`].
+    ///
+    /// See [`ArchivableRouter`] for details.
+    pub fn into_archivable(self) -> ArchivableRouter<T> {
+        ArchivableRouter {
+            root: ArchivableNode::new(self.root),
+        }
+    }
+}
+
+impl<T: Archive> ArchivedRouter<T> {
+    /// Tries to find a value in the archived router matching the given path.
+    ///
+    /// This has the exact same semantics as [`Router::at`].
+    pub fn at<'m, 'p>(
+        &'m self,
+        path: &'p str,
+    ) -> Result<Match<'m, 'p, &'m Archived<T>>, MatchError> {
+        match tree::at(&self.root, path.as_bytes()) {
+            Ok((value, params)) => Ok(Match { value, params }),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+// SAFETY: `verify` checks that the tree could have been created by inserting routes, which
+// is what matching relies on.
+unsafe impl<T, C> Verify<C> for ArchivedRouter<T>
+where
+    T: Archive,
+    C: Fallible + ?Sized,
+    C::Error: Source,
+{
+    fn verify(&self, _: &mut C) -> Result<(), C::Error> {
+        tree::validate(&self.root).map_err(|e| Source::new(InvalidArchive(e)))
+    }
+}
+
+// The error returned when accessing an archive with an invalid tree.
+#[derive(Debug)]
+struct InvalidArchive(&'static str);
+
+impl fmt::Display for InvalidArchive {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+impl core::error::Error for InvalidArchive {}
+
+impl<'n, T: Archive> Lookup<'n> for &'n ArchivedNode<T> {
+    type Value = Archived<T>;
+
+    fn prefix(self) -> &'n [u8] {
+        &self.prefix
+    }
+
+    fn indices(self) -> &'n [u8] {
+        &self.indices
+    }
+
+    fn node_type(self) -> NodeType {
+        NodeType::from(&self.node_type)
+    }
+
+    fn wild_child(self) -> bool {
+        self.wild_child
+    }
+
+    fn value(self) -> Option<&'n Archived<T>> {
+        self.value.as_ref()
+    }
+
+    fn children_len(self) -> usize {
+        self.children.len()
+    }
+
+    fn child(self, i: usize) -> Self {
+        &self.children[i]
+    }
+
+    fn param_name(self, i: usize) -> &'n [u8] {
+        &self.param_remapping[i][1..]
+    }
+}
+
+impl<'n, T: Archive> Untrusted<'n> for &'n ArchivedNode<T> {
+    fn remapping_len(self) -> usize {
+        self.param_remapping.len()
+    }
+
+    fn remapping(self, i: usize) -> &'n [u8] {
+        &self.param_remapping[i]
+    }
+}
+
+impl From<&ArchivedNodeType> for NodeType {
+    fn from(node_type: &ArchivedNodeType) -> Self {
+        match node_type {
+            ArchivedNodeType::Root => NodeType::Root,
+            ArchivedNodeType::Param => NodeType::Param,
+            ArchivedNodeType::CatchAll => NodeType::CatchAll,
+            ArchivedNodeType::Static => NodeType::Static,
+        }
+    }
+}
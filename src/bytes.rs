@@ -0,0 +1,176 @@
+//! Byte scanning routines used in the hot loops of the tree.
+//!
+//! Bytes are processed a word at a time, with SSE2 used for searching where it is
+//! available. Everything falls back to plain byte loops for the tails.
+
+use core::mem::size_of;
+
+const WORD: usize = size_of::<usize>();
+
+// `0x0101..01` and `0x8080..80`
+const LO: usize = usize::MAX / 255;
+const HI: usize = LO << 7;
+
+// Reads a word starting at `i`, with the first byte in the lowest bits regardless of
+// the platform's endianness.
+#[inline(always)]
+fn word(bytes: &[u8], i: usize) -> usize {
+    let mut word = [0; WORD];
+    word.copy_from_slice(&bytes[i..i + WORD]);
+    usize::from_le_bytes(word)
+}
+
+/// Returns the index of the first occurrence of `needle` in `haystack`.
+#[inline]
+pub(crate) fn find(needle: u8, haystack: &[u8]) -> Option<usize> {
+    #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+    {
+        sse2::find(needle, haystack)
+    }
+
+    #[cfg(not(all(target_arch = "x86_64", target_feature = "sse2")))]
+    {
+        find_words(needle, haystack, 0)
+    }
+}
+
+// Searches `haystack` for `needle` a word at a time, starting at `start`.
+#[inline]
+fn find_words(needle: u8, haystack: &[u8], start: usize) -> Option<usize> {
+    let repeated = LO * usize::from(needle);
+    let mut i = start;
+
+    while i + WORD <= haystack.len() {
+        // the bytes equal to the needle are now zero
+        let x = word(haystack, i) ^ repeated;
+
+        // sets the high bit of the first zero byte. borrows can only set bits in higher
+        // bytes, so the lowest set bit is always accurate
+        let zeros = x.wrapping_sub(LO) & !x & HI;
+        if zeros != 0 {
+            return Some(i + zeros.trailing_zeros() as usize / 8);
+        }
+
+        i += WORD;
+    }
+
+    haystack[i..]
+        .iter()
+        .position(|&b| b == needle)
+        .map(|pos| i + pos)
+}
+
+/// Returns the length of the longest common prefix of the two slices.
+#[inline]
+pub(crate) fn common_prefix(a: &[u8], b: &[u8]) -> usize {
+    let len = a.len().min(b.len());
+    let mut i = 0;
+
+    while i + WORD <= len {
+        let x = word(a, i) ^ word(b, i);
+        if x != 0 {
+            return i + x.trailing_zeros() as usize / 8;
+        }
+
+        i += WORD;
+    }
+
+    while i < len && a[i] == b[i] {
+        i += 1;
+    }
+
+    i
+}
+
+/// Returns `true` if the two slices are equal.
+///
+/// This avoids the call to `memcmp` for the short slices that are common in routes.
+#[inline]
+pub(crate) fn eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && common_prefix(a, b) == a.len()
+}
+
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+mod sse2 {
+    use core::arch::x86_64::{
+        __m128i, _mm_cmpeq_epi8, _mm_loadu_si128, _mm_movemask_epi8, _mm_set1_epi8,
+    };
+
+    const VECTOR: usize = 16;
+
+    #[inline]
+    pub(super) fn find(needle: u8, haystack: &[u8]) -> Option<usize> {
+        let mut i = 0;
+
+        if haystack.len() >= VECTOR {
+            // SAFETY: SSE2 is enabled at compile time, and all loads are in bounds
+            unsafe {
+                let repeated = _mm_set1_epi8(needle as i8);
+
+                while i + VECTOR <= haystack.len() {
+                    let chunk = _mm_loadu_si128(haystack.as_ptr().add(i).cast::<__m128i>());
+                    let mask = _mm_movemask_epi8(_mm_cmpeq_epi8(chunk, repeated));
+                    if mask != 0 {
+                        return Some(i + mask.trailing_zeros() as usize);
+                    }
+
+                    i += VECTOR;
+                }
+            }
+        }
+
+        super::find_words(needle, haystack, i)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::vec::Vec;
+
+    // paths of every length up to a few words, with the needle at every position
+    fn cases() -> impl Iterator<Item = (Vec<u8>, Option<usize>)> {
+        (0..50).flat_map(|len| {
+            let path = (0..len).map(|i| b'a' + (i % 26) as u8).collect::<Vec<_>>();
+            let unmatched = (path.clone(), None);
+
+            (0..len)
+                .map(move |pos| {
+                    let mut path = path.clone();
+                    path[pos] = b'/';
+                    // make sure only the first occurrence is found
+                    if pos + 3 < len {
+                        path[pos + 3] = b'/';
+                    }
+                    (path, Some(pos))
+                })
+                .chain(Some(unmatched))
+        })
+    }
+
+    #[test]
+    fn find_matches_naive() {
+        for (path, expected) in cases() {
+            assert_eq!(find(b'/', &path), expected, "{:?}", path);
+            assert_eq!(find_words(b'/', &path, 0), expected, "{:?}", path);
+        }
+
+        // bytes with the high bit set
+        let path = "/ʯ/β/ünìcodé/".as_bytes();
+        assert_eq!(find(0xce, path), path.iter().position(|&b| b == 0xce));
+        assert_eq!(find(b'/', &path[1..]), Some(2));
+    }
+
+    #[test]
+    fn common_prefix_matches_naive() {
+        let cases = cases().map(|(path, _)| path).collect::<Vec<_>>();
+
+        for path in &cases {
+            for other in cases.iter().filter(|other| other.len() <= path.len() + 1) {
+                let expected = path.iter().zip(other).take_while(|(a, b)| a == b).count();
+                assert_eq!(common_prefix(path, other), expected);
+                assert_eq!(eq(path, other), path == other);
+            }
+        }
+    }
+}
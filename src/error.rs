@@ -1,6 +1,8 @@
 use crate::alloc::borrow::ToOwned;
+use crate::syntax::Syntax;
 use crate::tree::{denormalize_params, Node};
 use alloc::string::String;
+use alloc::vec::Vec;
 
 use core::fmt;
 
@@ -13,12 +15,16 @@ pub enum InsertError {
         /// The existing route that the insertion is conflicting with.
         with: String,
     },
-    /// Only one parameter per route segment is allowed.
+    /// At most 26 named parameters per route are allowed.
     TooManyParams,
+    /// Only one parameter per route segment is allowed.
+    TooManySegmentParams,
     /// Parameters must be registered with a name.
     UnnamedParam,
     /// Catch-all parameters are only allowed at the end of a path.
     InvalidCatchAll,
+    /// Routes must start with `/` and only contain valid URL path characters.
+    InvalidRoute,
 }
 
 impl fmt::Display for InsertError {
@@ -31,22 +37,36 @@ impl fmt::Display for InsertError {
                     with
                 )
             }
-            Self::TooManyParams => write!(f, "only one parameter is allowed per path segment"),
+            Self::TooManyParams => write!(f, "at most 26 parameters are allowed per route"),
+            Self::TooManySegmentParams => {
+                write!(f, "only one parameter is allowed per path segment")
+            }
             Self::UnnamedParam => write!(f, "parameters must be registered with a name"),
             Self::InvalidCatchAll => write!(
                 f,
                 "catch-all parameters are only allowed at the end of a route"
             ),
+            Self::InvalidRoute => write!(
+                f,
+                "routes must start with '/' and only contain valid URL path characters"
+            ),
         }
     }
 }
 
+#[cfg(feature = "std")]
+impl std::error::Error for InsertError {}
+
 impl InsertError {
-    pub(crate) fn conflict<T>(route: &[u8], prefix: &[u8], current: &Node<T>) -> Self {
+    pub(crate) fn conflict<T, S: Syntax, M>(
+        route: &[u8],
+        prefix: &[u8],
+        current: &Node<T, S, M>,
+    ) -> Self {
         // The new route would have had to replace the current node in the tree.
         if prefix == current.prefix {
             let mut route = route.to_owned();
-            denormalize_params(&mut route, &current.param_remapping);
+            denormalize_params::<S>(&mut route, &current.param_remapping);
             return InsertError::Conflict {
                 with: String::from_utf8(route).unwrap(),
             };
@@ -69,7 +89,7 @@ impl InsertError {
             current = node.children.first();
         }
 
-        denormalize_params(&mut route, &last.param_remapping);
+        denormalize_params::<S>(&mut route, &last.param_remapping);
 
         InsertError::Conflict {
             with: String::from_utf8(route).unwrap(),
@@ -77,6 +97,44 @@ impl InsertError {
     }
 }
 
+/// Represents errors that can occur when combining routers with
+/// [`Router::nest`](crate::Router::nest) or [`Router::merge`](crate::Router::merge).
+///
+/// Contains every route that could not be inserted, along with its error, in the order
+/// they were attempted.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MergeError(pub(crate) Vec<(String, InsertError)>);
+
+impl MergeError {
+    /// Returns each route that could not be inserted, along with its error.
+    pub fn errors(&self) -> &[(String, InsertError)] {
+        &self.0
+    }
+
+    /// Consumes the error, returning each route that could not be inserted, along with
+    /// its error.
+    pub fn into_errors(self) -> Vec<(String, InsertError)> {
+        self.0
+    }
+}
+
+impl fmt::Display for MergeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (route, error)) in self.0.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+
+            write!(f, "{}: {}", route, error)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for MergeError {}
+
 /// A failed match attempt.
 ///
 /// ```
@@ -113,8 +171,8 @@ pub enum MatchError {
 }
 
 impl MatchError {
-    pub(crate) fn unsure(full_path: &[u8]) -> Self {
-        if full_path[full_path.len() - 1] == b'/' {
+    pub(crate) fn unsure(full_path: &[u8], sep: u8) -> Self {
+        if full_path[full_path.len() - 1] == sep {
             MatchError::ExtraTrailingSlash
         } else {
             MatchError::MissingTrailingSlash
@@ -133,3 +191,75 @@ impl fmt::Display for MatchError {
         write!(f, "{}", msg)
     }
 }
+
+#[cfg(feature = "std")]
+impl std::error::Error for MatchError {}
+
+/// A failed match attempt with a [`MethodRouter`](crate::MethodRouter).
+///
+/// ```
+/// use matchit::{MatchError, MethodError, MethodRouter};
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut router = MethodRouter::new();
+/// router.insert("GET", "/home", "Welcome!")?;
+///
+/// // no routes match
+/// assert_eq!(
+///     router.at("GET", "/foobar").unwrap_err(),
+///     MethodError::NotFound(MatchError::NotFound)
+/// );
+///
+/// // a route matches, but not for this method
+/// assert_eq!(
+///     router.at("POST", "/home").unwrap_err(),
+///     MethodError::NotAllowed { allow: "GET, HEAD, OPTIONS" }
+/// );
+///
+/// // an `OPTIONS` request for a route without an explicit value
+/// assert_eq!(
+///     router.at("OPTIONS", "/home").unwrap_err(),
+///     MethodError::Options { allow: "GET, HEAD, OPTIONS" }
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum MethodError<'m> {
+    /// No route matches the path, see [`MatchError`].
+    NotFound(MatchError),
+    /// A route matches the path, but has no value for the method.
+    NotAllowed {
+        /// The value of the `Allow` header listing the methods of the route.
+        allow: &'m str,
+    },
+    /// A route matches the path of an `OPTIONS` request, but has no value for it.
+    Options {
+        /// The value of the `Allow` header listing the methods of the route.
+        allow: &'m str,
+    },
+}
+
+impl fmt::Display for MethodError<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MethodError::NotFound(err) => write!(f, "{}", err),
+            MethodError::NotAllowed { allow } => {
+                write!(
+                    f,
+                    "match error: method not allowed, expected one of {}",
+                    allow
+                )
+            }
+            MethodError::Options { allow } => {
+                write!(
+                    f,
+                    "match error: no value for OPTIONS, allowed methods are {}",
+                    allow
+                )
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for MethodError<'_> {}
@@ -0,0 +1,267 @@
+use crate::bytes;
+use crate::tree::{self, Lookup, Node, NodeType};
+use crate::{Match, MatchError, MatchScratch};
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// A compact, read-only router created with [`Router::freeze`](crate::Router::freeze).
+///
+/// The tree is compacted into a flat list of nodes with contiguous children, and all
+/// prefixes are stored in a single buffer. Nodes with many children also get a lookup
+/// table indexed by the next byte of the path, instead of scanning their children.
+///
+/// Matching has the exact same semantics as [`Router::at`](crate::Router::at).
+///
+/// ```rust
+/// use matchit::Router;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut router = Router::new();
+/// router.insert("/home", "Welcome!")?;
+/// router.insert("/users/:id", "A User")?;
+///
+/// let router = router.freeze();
+/// let matched = router.at("/users/978")?;
+/// assert_eq!(matched.params.get("id"), Some("978"));
+/// assert_eq!(*matched.value, "A User");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct FrozenRouter<T> {
+    nodes: Vec<FrozenNode>,
+    // the prefixes, indices, and parameter names of all nodes
+    bytes: Vec<u8>,
+    // the parameter names of all nodes, as spans of `bytes`
+    params: Vec<Span>,
+    tables: Vec<[u8; 256]>,
+    values: Vec<T>,
+}
+
+// Nodes with at least this many static children get a lookup table.
+const TABLE_THRESHOLD: usize = 4;
+
+// Marks a node without a value or lookup table.
+const NONE: u32 = u32::MAX;
+
+// A range of one of the buffers of a router.
+#[derive(Clone, Copy)]
+struct Span {
+    start: u32,
+    len: u32,
+}
+
+impl Span {
+    fn new(start: usize, len: usize) -> Span {
+        Span {
+            start: offset(start),
+            len: offset(len),
+        }
+    }
+
+    fn range(self) -> core::ops::Range<usize> {
+        self.start as usize..(self.start + self.len) as usize
+    }
+}
+
+// Converts an offset into one of the buffers of a router.
+fn offset(i: usize) -> u32 {
+    u32::try_from(i).expect("router is too large to freeze")
+}
+
+// A node of a frozen router.
+//
+// Nodes refer to their data through offsets into the buffers of the router, which keeps
+// them small and lets the router be moved and cloned freely.
+#[derive(Clone)]
+struct FrozenNode {
+    // spans of `bytes`
+    prefix: Span,
+    indices: Span,
+    // a span of `params`
+    params: Span,
+    // a span of `nodes`
+    children: Span,
+    // an index into `values`, or `NONE`
+    value: u32,
+    // an index into `tables`, or `NONE`
+    table: u32,
+    node_type: NodeType,
+    wild_child: bool,
+}
+
+impl<T> FrozenRouter<T> {
+    pub(crate) fn new(root: Node<T>) -> Self {
+        let mut bytes = Vec::new();
+        let mut params = Vec::new();
+        let mut tables = Vec::new();
+        let mut values = Vec::new();
+        let mut nodes = Vec::new();
+
+        let push_bytes = |bytes: &mut Vec<u8>, slice: &[u8]| {
+            bytes.extend_from_slice(slice);
+            Span::new(bytes.len() - slice.len(), slice.len())
+        };
+
+        // lay out the nodes depth-first, keeping the children of each node contiguous
+        let mut next = 1;
+        let mut stack = vec![(root, 0)];
+
+        while let Some((node, slot)) = stack.pop() {
+            let children = Span::new(next, node.children.len());
+            next += node.children.len();
+
+            let table = if node.indices.len() >= TABLE_THRESHOLD && node.indices.len() < 256 {
+                let mut table = [0; 256];
+                for (i, &c) in node.indices.iter().enumerate() {
+                    table[c as usize] = i as u8 + 1;
+                }
+
+                tables.push(table);
+                offset(tables.len() - 1)
+            } else {
+                NONE
+            };
+
+            let param_names = Span::new(params.len(), node.param_remapping.len());
+            for param in &node.param_remapping {
+                params.push(push_bytes(&mut bytes, &param[1..]));
+            }
+
+            let value = match node.value {
+                Some(value) => {
+                    values.push(value.into_inner().0);
+                    offset(values.len() - 1)
+                }
+                None => NONE,
+            };
+
+            if nodes.len() < next {
+                nodes.resize_with(next, || None);
+            }
+
+            nodes[slot] = Some(FrozenNode {
+                prefix: push_bytes(&mut bytes, &node.prefix),
+                indices: push_bytes(&mut bytes, &node.indices),
+                params: param_names,
+                children,
+                value,
+                table,
+                node_type: node.node_type,
+                wild_child: node.wild_child,
+            });
+
+            let slots = (children.start as usize..)
+                .zip(node.children)
+                .collect::<Vec<_>>();
+            stack.extend(slots.into_iter().rev().map(|(slot, child)| (child, slot)));
+        }
+
+        FrozenRouter {
+            nodes: nodes.into_iter().map(Option::unwrap).collect(),
+            bytes,
+            params,
+            tables,
+            values,
+        }
+    }
+
+    // Returns the root node.
+    fn root(&self) -> FrozenRef<'_, T> {
+        FrozenRef {
+            router: self,
+            node: &self.nodes[0],
+        }
+    }
+
+    /// Tries to find a value in the router matching the given path.
+    ///
+    /// See [`Router::at`](crate::Router::at) for details.
+    pub fn at<'m, 'p>(&'m self, path: &'p str) -> Result<Match<'m, 'p, &'m T>, MatchError> {
+        match tree::at(self.root(), path.as_bytes()) {
+            Ok((value, params)) => Ok(Match { value, params }),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Tries to find a value in the router matching the given path, without allocating.
+    ///
+    /// See [`Router::at_with`](crate::Router::at_with) for details.
+    pub fn at_with<'m, 'p: 's, 's>(
+        &'m self,
+        scratch: &'s mut MatchScratch<'m, FrozenRef<'m, T>>,
+        path: &'p str,
+    ) -> Result<Match<'s, 's, &'m T>, MatchError> {
+        match tree::at_with(self.root(), scratch, path.as_bytes()) {
+            Ok((value, params)) => Ok(Match { value, params }),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+// A reference to a node of a frozen router, along with the router it belongs to.
+pub struct FrozenRef<'n, T> {
+    router: &'n FrozenRouter<T>,
+    node: &'n FrozenNode,
+}
+
+impl<T> Clone for FrozenRef<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for FrozenRef<'_, T> {}
+
+impl<'n, T> Lookup<'n> for FrozenRef<'n, T> {
+    type Value = T;
+
+    fn prefix(self) -> &'n [u8] {
+        &self.router.bytes[self.node.prefix.range()]
+    }
+
+    fn indices(self) -> &'n [u8] {
+        &self.router.bytes[self.node.indices.range()]
+    }
+
+    fn node_type(self) -> NodeType {
+        self.node.node_type
+    }
+
+    fn wild_child(self) -> bool {
+        self.node.wild_child
+    }
+
+    fn value(self) -> Option<&'n T> {
+        match self.node.value {
+            NONE => None,
+            i => Some(&self.router.values[i as usize]),
+        }
+    }
+
+    fn children_len(self) -> usize {
+        self.node.children.len as usize
+    }
+
+    fn child(self, i: usize) -> Self {
+        FrozenRef {
+            router: self.router,
+            node: &self.router.nodes[self.node.children.range()][i],
+        }
+    }
+
+    fn param_name(self, i: usize) -> &'n [u8] {
+        let param = self.router.params[self.node.params.range()][i];
+        &self.router.bytes[param.range()]
+    }
+
+    fn static_child(self, first: u8) -> Option<Self> {
+        match self.node.table {
+            NONE => bytes::find(first, self.indices()).map(|i| self.child(i)),
+            table => match self.router.tables[table as usize][first as usize] {
+                0 => None,
+                i => Some(self.child(i as usize - 1)),
+            },
+        }
+    }
+}
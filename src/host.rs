@@ -0,0 +1,281 @@
+use crate::syntax::Syntax;
+use crate::tree::Node;
+use crate::{InsertError, Match, MatchError, Params};
+use alloc::string::String;
+use alloc::vec;
+
+// The maximum length of a hostname.
+const MAX_HOST: usize = 253;
+
+// The name given to a catch-all without a name, like in `*.example.com`. It can't be
+// written in a route, and is left out of the parameters of a match.
+const UNNAMED: &[u8] = b"~";
+
+/// A router that matches hostnames, such as the `Host` header of a request.
+///
+/// Routes are hostnames whose labels can be named parameters, like `:tenant.example.com`,
+/// which match a single label. The leftmost label can also be a catch-all parameter, like
+/// `*sub.cdn.example.com`, which matches one or more labels. A catch-all without a name,
+/// like `*.cdn.example.com`, is not included in the parameters of a match. Parameters
+/// always span a whole label, and like in paths, static labels are prioritized over them.
+///
+/// Hostnames are matched case-insensitively, and a single trailing dot is ignored. Hosts
+/// should be matched without a port.
+///
+/// ```rust
+/// use matchit::HostRouter;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut router = HostRouter::new();
+/// router.insert("www.example.com", "Home")?;
+/// router.insert(":tenant.example.com", "A Tenant")?;
+/// router.insert("*sub.cdn.example.com", "CDN")?;
+///
+/// let matched = router.at("acme.example.com")?;
+/// assert_eq!(matched.params.get("tenant"), Some("acme"));
+/// assert_eq!(*matched.value, "A Tenant");
+///
+/// let matched = router.at("eu.img.cdn.example.com")?;
+/// assert_eq!(matched.params.get("sub"), Some("eu.img"));
+///
+/// assert_eq!(*router.at("WWW.Example.com.")?.value, "Home");
+/// # Ok(())
+/// # }
+/// ```
+pub struct HostRouter<T> {
+    // the routes with their labels in reverse order, so that catch-alls are at the end
+    root: Node<T, Host>,
+}
+
+// Hostnames with their labels in reverse order.
+struct Host;
+
+impl Syntax for Host {
+    const SEPARATOR: u8 = b'.';
+    const PARAM: u8 = b':';
+    const CATCH_ALL: u8 = b'*';
+}
+
+impl<T> Default for HostRouter<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> HostRouter<T> {
+    /// Construct a new router.
+    pub const fn new() -> Self {
+        Self { root: Node::new() }
+    }
+
+    /// Insert a route for a hostname into the router.
+    ///
+    /// ```rust
+    /// # use matchit::HostRouter;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut router = HostRouter::new();
+    /// router.insert("api.example.com", "API")?;
+    /// router.insert(":tenant.example.com", "A Tenant")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn insert(&mut self, host: impl Into<String>, value: T) -> Result<(), InsertError> {
+        let route = to_route(host.into())?;
+
+        self.root.insert(route, value).map_err(|err| match err {
+            // report the conflicting route as it was inserted
+            InsertError::Conflict { with } => {
+                let mut route = vec![0; with.len()];
+                reverse_labels(with.as_bytes(), &mut route);
+
+                if route.starts_with(b"*") && route[1..].starts_with(UNNAMED) {
+                    route.drain(1..1 + UNNAMED.len());
+                }
+
+                InsertError::Conflict {
+                    with: String::from_utf8(route).unwrap(),
+                }
+            }
+            err => err,
+        })
+    }
+
+    /// Tries to find a value in the router matching the given hostname.
+    pub fn at<'m, 'h>(&'m self, host: &'h str) -> Result<Match<'m, 'h, &'m T>, MatchError> {
+        let (value, params) = self.find(host)?;
+
+        Ok(Match {
+            // SAFETY: We only expose &mut T through &mut self
+            value: unsafe { &*value },
+            params,
+        })
+    }
+
+    /// Tries to find a value in the router matching the given hostname,
+    /// returning a mutable reference.
+    pub fn at_mut<'m, 'h>(
+        &'m mut self,
+        host: &'h str,
+    ) -> Result<Match<'m, 'h, &'m mut T>, MatchError> {
+        let (value, params) = self.find(host)?;
+
+        Ok(Match {
+            // SAFETY: We have &mut self
+            value: unsafe { &mut *value },
+            params,
+        })
+    }
+
+    // Returns the value of the given route, if it was already inserted.
+    pub(crate) fn route_mut(&mut self, host: &str) -> Option<&mut T> {
+        let route = to_route(host.into()).ok()?;
+        self.root.route_mut(route.as_bytes())
+    }
+
+    pub(crate) fn find<'m, 'h>(
+        &'m self,
+        host: &'h str,
+    ) -> Result<(*mut T, Params<'m, 'h>), MatchError> {
+        self.find_map(host, |value, params| Some((value, params)))
+            .ok_or(MatchError::NotFound)
+    }
+
+    // Calls `f` with every route matching the given hostname in priority order, until it
+    // returns `Some`.
+    pub(crate) fn find_map<'m, 'h, R>(
+        &'m self,
+        host: &'h str,
+        mut f: impl FnMut(*mut T, Params<'m, 'h>) -> Option<R>,
+    ) -> Option<R> {
+        let host = host.as_bytes();
+        let host = host.strip_suffix(b".").unwrap_or(host);
+
+        if host.is_empty() || host.len() > MAX_HOST {
+            return None;
+        }
+
+        let mut buf = [0; MAX_HOST];
+        let reversed = &mut buf[..host.len()];
+        // labels are reversed as a whole, so this is still valid UTF-8
+        reverse_labels(host, reversed);
+        reversed.make_ascii_lowercase();
+
+        for (value, reversed_params) in self.root.matches(reversed) {
+            // the parameters point into the reversed hostname, so find them in the original
+            let mut params = Params::new();
+            for (key, value) in reversed_params.iter() {
+                if key.as_bytes() == UNNAMED {
+                    continue;
+                }
+
+                let start = value.as_ptr() as usize - reversed.as_ptr() as usize;
+                let range = mirror(reversed, start, value.len());
+                params.push(key.as_bytes(), &host[range]);
+            }
+
+            if let Some(result) = f(value.value.get(), params) {
+                return Some(result);
+            }
+        }
+
+        None
+    }
+}
+
+// Converts a hostname into a route of the tree, with its labels in reverse order.
+fn to_route(host: String) -> Result<String, InsertError> {
+    let mut host = host.into_bytes();
+    validate_host(&mut host)?;
+
+    if host == b"*" || host.starts_with(b"*.") {
+        host.splice(1..1, UNNAMED.iter().copied());
+    }
+
+    let mut reversed = vec![0; host.len()];
+    reverse_labels(&host, &mut reversed);
+
+    // the route is valid ASCII, see `validate_host`
+    Ok(String::from_utf8(reversed).unwrap())
+}
+
+// Writes the labels of `host` into `out` in reverse order.
+fn reverse_labels(host: &[u8], out: &mut [u8]) {
+    let mut i = 0;
+
+    for label in host.rsplit(|&b| b == b'.') {
+        if i > 0 {
+            out[i] = b'.';
+            i += 1;
+        }
+
+        out[i..i + label.len()].copy_from_slice(label);
+        i += label.len();
+    }
+}
+
+// Returns the range of the hostname corresponding to `len` bytes at `start` in the
+// reversed hostname, which either lie within a single label or span whole labels.
+fn mirror(reversed: &[u8], start: usize, len: usize) -> core::ops::Range<usize> {
+    let end = start + len;
+
+    let label_start = reversed[..start]
+        .iter()
+        .rposition(|&b| b == b'.')
+        .map_or(0, |i| i + 1);
+
+    let label_end = reversed[end..]
+        .iter()
+        .position(|&b| b == b'.')
+        .map_or(reversed.len(), |i| end + i);
+
+    // the labels are mirrored, while the bytes within them keep their order
+    let start = reversed.len() - label_end + (start - label_start);
+    start..start + len
+}
+
+// Checks that the route is a valid hostname, with parameters only at the start of a label
+// and a catch-all only in the leftmost label, and lowercases its static labels.
+fn validate_host(host: &mut [u8]) -> Result<(), InsertError> {
+    if host.is_empty() || host.len() > MAX_HOST {
+        return Err(InsertError::InvalidRoute);
+    }
+
+    let wildcard = |b: &u8| matches!(b, b':' | b'*');
+
+    for (i, label) in host.split_mut(|&b| b == b'.').enumerate() {
+        if label.is_empty() {
+            return Err(InsertError::InvalidRoute);
+        }
+
+        if !label
+            .iter()
+            .all(|&b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b':' | b'*'))
+        {
+            return Err(InsertError::InvalidRoute);
+        }
+
+        // a parameter is a whole label
+        if label[1..].iter().any(wildcard) {
+            if wildcard(&label[0]) {
+                return Err(InsertError::TooManySegmentParams);
+            }
+
+            if label.contains(&b'*') {
+                return Err(InsertError::InvalidCatchAll);
+            }
+
+            return Err(InsertError::InvalidRoute);
+        }
+
+        if label[0] == b'*' && i != 0 {
+            return Err(InsertError::InvalidCatchAll);
+        }
+
+        // parameter names keep their case
+        if !wildcard(&label[0]) {
+            label.make_ascii_lowercase();
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,155 @@
+use crate::syntax::Syntax;
+use crate::tree::{self, Node};
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+use core::ptr::NonNull;
+
+/// An index of all routes without parameters, so that they can be matched without
+/// walking the tree.
+///
+/// The index holds pointers to the values in the tree, which are stored at stable
+/// addresses. It must be rebuilt whenever values are removed from the tree.
+#[cfg_attr(test, derive(Debug))]
+pub(crate) struct StaticIndex<T, M = ()> {
+    // an open addressing table with linear probing, whose length is zero or a power of two
+    slots: Vec<Option<Entry<T, M>>>,
+    len: usize,
+    // a bitset of the lengths of all routes, with longer routes sharing the last bit. this
+    // lets most paths with parameters skip hashing entirely
+    lengths: u64,
+}
+
+#[cfg_attr(test, derive(Debug))]
+struct Entry<T, M> {
+    hash: u64,
+    route: Box<[u8]>,
+    value: NonNull<tree::Entry<T, M>>,
+}
+
+// SAFETY: the index only hands out shared references to the cells, and the router follows
+// the usual borrowing rules for the values inside them
+unsafe impl<T: Send, M: Send> Send for StaticIndex<T, M> {}
+unsafe impl<T: Sync, M: Sync> Sync for StaticIndex<T, M> {}
+
+impl<T, M> StaticIndex<T, M> {
+    pub const fn new() -> Self {
+        Self {
+            slots: Vec::new(),
+            len: 0,
+            lengths: 0,
+        }
+    }
+
+    // Indexes all routes without parameters in the tree.
+    pub fn build<S: Syntax>(root: &Node<T, S, M>) -> Self {
+        let mut index = Self::new();
+        root.static_routes(&mut |route, value| index.insert(route, value));
+        index
+    }
+
+    // Returns the value of the route matching the path exactly, if any.
+    //
+    // The returned reference is only valid as long as the value is still in the tree.
+    #[inline]
+    pub fn get(&self, path: &[u8]) -> Option<&tree::Entry<T, M>> {
+        if self.lengths & length_bit(path) == 0 {
+            return None;
+        }
+
+        let hash = hash(path);
+        let mask = self.slots.len() - 1;
+        let mut i = hash as usize & mask;
+
+        // the table is never full, so there is always an empty slot to stop at. the routes
+        // are fixed, so the probe sequences can't be made longer by the path
+        loop {
+            match &self.slots[i] {
+                None => return None,
+                Some(entry) if entry.hash == hash && *entry.route == *path => {
+                    // SAFETY: the value is still in the tree, see above
+                    return Some(unsafe { entry.value.as_ref() });
+                }
+                Some(_) => i = (i + 1) & mask,
+            }
+        }
+    }
+
+    // Adds a route to the index, replacing any previous value for it.
+    pub fn insert(&mut self, route: &[u8], value: &tree::Entry<T, M>) {
+        // keep the table at most half full
+        if (self.len + 1) * 2 > self.slots.len() {
+            self.grow();
+        }
+
+        let entry = Entry {
+            hash: hash(route),
+            route: route.into(),
+            value: NonNull::from(value),
+        };
+
+        if self.insert_entry(entry) {
+            self.len += 1;
+            self.lengths |= length_bit(route);
+        }
+    }
+
+    // Inserts an entry, returning `true` if the route was not already present.
+    fn insert_entry(&mut self, entry: Entry<T, M>) -> bool {
+        let mask = self.slots.len() - 1;
+        let mut i = entry.hash as usize & mask;
+
+        loop {
+            match &mut self.slots[i] {
+                slot @ None => {
+                    *slot = Some(entry);
+                    return true;
+                }
+                Some(existing) if existing.route == entry.route => {
+                    *existing = entry;
+                    return false;
+                }
+                Some(_) => i = (i + 1) & mask,
+            }
+        }
+    }
+
+    fn grow(&mut self) {
+        let capacity = (self.slots.len() * 2).max(8);
+        let slots = (0..capacity).map(|_| None).collect();
+
+        for entry in core::mem::replace(&mut self.slots, slots)
+            .into_iter()
+            .flatten()
+        {
+            self.insert_entry(entry);
+        }
+    }
+}
+
+// Returns the bit for the length of the path in `StaticIndex::lengths`.
+#[inline]
+fn length_bit(path: &[u8]) -> u64 {
+    1 << path.len().min(63)
+}
+
+// Hashes a path a word at a time.
+#[inline]
+fn hash(path: &[u8]) -> u64 {
+    const SEED: u64 = 0x51_7c_c1_b7_27_22_0a_95;
+
+    let mut hash = path.len() as u64;
+    let mut chunks = path.chunks_exact(8);
+
+    for chunk in &mut chunks {
+        let word = u64::from_le_bytes(chunk.try_into().unwrap());
+        hash = (hash.rotate_left(5) ^ word).wrapping_mul(SEED);
+    }
+
+    let mut rest = [0; 8];
+    rest[..chunks.remainder().len()].copy_from_slice(chunks.remainder());
+    hash = (hash.rotate_left(5) ^ u64::from_le_bytes(rest)).wrapping_mul(SEED);
+
+    // the low bits are used to pick a slot, so mix in the high bits
+    hash ^ (hash >> 32)
+}
@@ -103,14 +103,54 @@
 #![no_std]
 #![deny(rust_2018_idioms, clippy::all)]
 extern crate alloc;
+#[cfg(any(test, feature = "std"))]
+#[cfg_attr(test, macro_use)]
+extern crate std;
+#[cfg(feature = "rkyv")]
+mod archive;
+mod bytes;
 mod error;
+mod frozen;
+mod host;
+mod index;
+mod lint;
+mod method;
 mod params;
+mod query;
 mod router;
+#[cfg(feature = "serde")]
+mod serialize;
+#[cfg(feature = "tower")]
+mod service;
+mod static_router;
+mod syntax;
+mod topic;
 mod tree;
+mod vhost;
 
-pub use error::{InsertError, MatchError};
-pub use params::{Params, ParamsIter};
-pub use router::{Match, Router};
+#[cfg(feature = "rkyv")]
+pub use archive::{ArchivableRouter, ArchivedRouter};
+pub use error::{InsertError, MatchError, MergeError, MethodError};
+pub use frozen::FrozenRouter;
+pub use host::HostRouter;
+pub use lint::{lint, Lint};
+pub use method::MethodRouter;
+pub use params::{BytesParams, OwnedParams, Params, ParamsIter};
+pub use query::QueryRouter;
+pub use router::{BytesMatch, Match, Matches, Router, Routes, ValuesMut};
+#[cfg(feature = "tower")]
+pub use service::{RouteFuture, RouterLayer, RouterService};
+pub use static_router::{StaticNode, StaticRouter};
+pub use syntax::{Path, Syntax};
+pub use topic::TopicRouter;
+pub use tree::MatchScratch;
+pub use vhost::VirtualRouter;
+
+// used by generated static tables
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::static_router::v1;
+}
 
 #[cfg(doctest)]
 mod test_readme {
@@ -0,0 +1,408 @@
+use crate::syntax::Path;
+use crate::tree::normalize_params;
+use crate::{InsertError, Router};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use core::fmt;
+
+/// A potential problem found in a set of routes by [`lint`].
+#[non_exhaustive]
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Lint {
+    /// The route was already registered, possibly with different parameter names.
+    Duplicate {
+        /// The duplicate route.
+        route: String,
+        /// The route that was registered first.
+        with: String,
+    },
+    /// The route could not be inserted into a router.
+    Invalid {
+        /// The invalid route.
+        route: String,
+        /// The error returned when inserting the route.
+        error: InsertError,
+    },
+    /// A path that matches the route is never routed to it, because static segments
+    /// of another route take priority over its parameters.
+    Shadowed {
+        /// The shadowed route.
+        route: String,
+        /// A path that matches the route, but is not routed to it.
+        path: String,
+        /// The route with the static segment that takes priority, if any. If there is none,
+        /// the route can never be matched.
+        by: Option<String>,
+    },
+    /// Overlapping routes use different names for the same parameter.
+    InconsistentParams {
+        /// The route using a different name.
+        route: String,
+        /// The route that was registered first.
+        with: String,
+    },
+    /// The routes differ only by a trailing slash.
+    TrailingSlash {
+        /// The route that was registered last.
+        route: String,
+        /// The route that was registered first.
+        with: String,
+    },
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Duplicate { route, with } => {
+                write!(f, "route {} is a duplicate of {}", route, with)
+            }
+            Self::Invalid { route, error } => write!(f, "route {} is invalid: {}", route, error),
+            Self::Shadowed {
+                route,
+                path,
+                by: Some(by),
+            } => write!(
+                f,
+                "route {} does not match {} because {} takes priority",
+                route, path, by
+            ),
+            Self::Shadowed {
+                route,
+                path,
+                by: None,
+            } => write!(
+                f,
+                "route {} does not match {} and is unreachable",
+                route, path
+            ),
+            Self::InconsistentParams { route, with } => write!(
+                f,
+                "route {} uses different parameter names than {}",
+                route, with
+            ),
+            Self::TrailingSlash { route, with } => write!(
+                f,
+                "route {} differs from {} only by a trailing slash",
+                route, with
+            ),
+        }
+    }
+}
+
+/// Checks a set of routes for potential problems, without stopping at the first one.
+///
+/// This reports:
+/// - Routes that are registered more than once, or that cannot be inserted.
+/// - Routes that never match some of their paths because static routes take priority
+///   over their parameters, or that can never be matched at all.
+/// - Overlapping routes that use different names for the same parameter.
+/// - Routes that differ only by a trailing slash.
+///
+/// Problems are returned in the order of the routes they were found in.
+///
+/// # Examples
+///
+/// ```rust
+/// use matchit::Lint;
+///
+/// let lints = matchit::lint([
+///     "/users/:id/",
+///     "/users/:user_id/posts",
+///     "/users/new",
+///     "/users/new/",
+/// ]);
+///
+/// assert_eq!(
+///     lints,
+///     [
+///         Lint::InconsistentParams {
+///             route: "/users/:user_id/posts".into(),
+///             with: "/users/:id/".into(),
+///         },
+///         Lint::TrailingSlash {
+///             route: "/users/new/".into(),
+///             with: "/users/new".into(),
+///         },
+///     ]
+/// );
+/// ```
+pub fn lint<I>(routes: I) -> Vec<Lint>
+where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+{
+    let mut lints = Vec::new();
+    let mut router = Router::new();
+    let mut parsed: Vec<Parsed> = Vec::new();
+
+    for route in routes {
+        let route = route.as_ref();
+
+        let (normalized, params) = match normalize_params::<Path>(route.as_bytes().to_vec()) {
+            Ok(parsed) => parsed,
+            Err(error) => {
+                lints.push(Lint::Invalid {
+                    route: route.to_string(),
+                    error,
+                });
+                continue;
+            }
+        };
+
+        let current = Parsed {
+            route: route.to_string(),
+            normalized,
+            params,
+        };
+
+        if let Some(other) = parsed.iter().find(|p| p.normalized == current.normalized) {
+            lints.push(Lint::Duplicate {
+                route: current.route,
+                with: other.route.clone(),
+            });
+            continue;
+        }
+
+        if let Err(error) = router.insert(route, parsed.len()) {
+            lints.push(Lint::Invalid {
+                route: current.route,
+                error,
+            });
+            continue;
+        }
+
+        for other in &parsed {
+            if current.inconsistent_with(other) {
+                lints.push(Lint::InconsistentParams {
+                    route: current.route.clone(),
+                    with: other.route.clone(),
+                });
+            }
+
+            if differ_by_slash(&current.normalized, &other.normalized) {
+                lints.push(Lint::TrailingSlash {
+                    route: current.route.clone(),
+                    with: other.route.clone(),
+                });
+            }
+        }
+
+        parsed.push(current);
+    }
+
+    // now that we know all the routes, make sure each of them is reachable
+    for (i, route) in parsed.iter().enumerate() {
+        if let Some(lint) = shadowed(&router, &parsed, i, route) {
+            lints.push(lint);
+        }
+    }
+
+    lints
+}
+
+// A route that was successfully parsed.
+struct Parsed {
+    route: String,
+    normalized: Vec<u8>,
+    params: Vec<Vec<u8>>,
+}
+
+impl Parsed {
+    // Returns `true` if the two routes share a parameter with different names.
+    fn inconsistent_with(&self, other: &Parsed) -> bool {
+        let shared = self
+            .normalized
+            .iter()
+            .zip(other.normalized.iter())
+            .take_while(|(a, b)| a == b)
+            .filter(|(&a, _)| a == b':')
+            .count();
+
+        self.params[..shared] != other.params[..shared]
+    }
+}
+
+// Returns `true` if the routes differ only by a trailing slash.
+fn differ_by_slash(a: &[u8], b: &[u8]) -> bool {
+    a.strip_suffix(b"/") == Some(b) || b.strip_suffix(b"/") == Some(a)
+}
+
+// A value for parameters that no static route can contain.
+const SAMPLE: &str = " ";
+
+// Probes the router with paths matching the route at `index`, returning the first one that
+// is not routed to it, or routed to a route that shouldn't match it.
+fn shadowed(
+    router: &Router<usize>,
+    parsed: &[Parsed],
+    index: usize,
+    route: &Parsed,
+) -> Option<Lint> {
+    let segments = route.route.split('/').collect::<Vec<_>>();
+
+    let probe = |path: String, by: Option<&Parsed>| match router.at(&path) {
+        // another route matching this path took priority, which is expected
+        Ok(_) => None,
+        Err(_) => Some(Lint::Shadowed {
+            route: route.route.clone(),
+            path,
+            by: by.map(|by| by.route.clone()),
+        }),
+    };
+
+    // try a path that no other static route could match first
+    let sample = segments.iter().map(|s| sample(s)).collect::<Vec<_>>();
+
+    match router.at(&sample.join("/")) {
+        Ok(matched) if *matched.value == index => {}
+        matched => {
+            return Some(Lint::Shadowed {
+                route: route.route.clone(),
+                path: sample.join("/"),
+                by: matched.ok().map(|m| parsed[*m.value].route.clone()),
+            })
+        }
+    }
+
+    // try replacing each parameter with overlapping static segments of other routes
+    for (i, segment) in segments.iter().enumerate() {
+        if !matches!(segment.as_bytes().first(), Some(b':' | b'*')) {
+            continue;
+        }
+
+        for other in parsed {
+            let value = match other.route.split('/').nth(i) {
+                Some(value) if !value.is_empty() && !value.contains(|c| matches!(c, ':' | '*')) => {
+                    value
+                }
+                _ => continue,
+            };
+
+            let mut path = sample.clone();
+            path[i] = String::from(value);
+
+            if let Some(lint) = probe(path.join("/"), Some(other)) {
+                return Some(lint);
+            }
+        }
+    }
+
+    None
+}
+
+// Replaces any parameter in the segment with `SAMPLE`.
+fn sample(segment: &str) -> String {
+    match segment.find(|c| matches!(c, ':' | '*')) {
+        Some(i) => [&segment[..i], SAMPLE].concat(),
+        None => String::from(segment),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn duplicates() {
+        let lints = lint([
+            "/users/:id",
+            "/users/:name",
+            "/users/:id",
+            "/files/*a",
+            "/files/*b",
+        ]);
+
+        assert_eq!(
+            lints,
+            [
+                Lint::Duplicate {
+                    route: "/users/:name".into(),
+                    with: "/users/:id".into(),
+                },
+                Lint::Duplicate {
+                    route: "/users/:id".into(),
+                    with: "/users/:id".into(),
+                },
+                Lint::Invalid {
+                    route: "/files/*b".into(),
+                    error: InsertError::Conflict {
+                        with: "/files/*a".into()
+                    },
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        let lints = lint(["/:a:b", "users", "/ok"]);
+
+        assert_eq!(
+            lints,
+            [
+                Lint::Invalid {
+                    route: "/:a:b".into(),
+                    error: InsertError::TooManySegmentParams,
+                },
+                Lint::Invalid {
+                    route: "users".into(),
+                    error: InsertError::InvalidRoute,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn shadowed() {
+        let lints = lint(["/users/:id/", "/users/new"]);
+
+        assert_eq!(
+            lints,
+            [Lint::Shadowed {
+                route: "/users/:id/".into(),
+                path: "/users/new/".into(),
+                by: Some("/users/new".into()),
+            }]
+        );
+
+        // static routes overriding parameters is expected
+        assert!(lint(["/users/:id", "/users/new", "/users/:id/posts"]).is_empty());
+    }
+
+    #[test]
+    fn inconsistent_params() {
+        let lints = lint(["/users/:id", "/users/:user_id/posts", "/posts/:user_id"]);
+
+        assert_eq!(
+            lints,
+            [Lint::InconsistentParams {
+                route: "/users/:user_id/posts".into(),
+                with: "/users/:id".into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn trailing_slash() {
+        let lints = lint(["/users/:id/", "/users/:name", "/posts", "/posts/"]);
+
+        assert_eq!(
+            lints,
+            [
+                Lint::InconsistentParams {
+                    route: "/users/:name".into(),
+                    with: "/users/:id/".into(),
+                },
+                Lint::TrailingSlash {
+                    route: "/users/:name".into(),
+                    with: "/users/:id/".into(),
+                },
+                Lint::TrailingSlash {
+                    route: "/posts/".into(),
+                    with: "/posts".into(),
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,195 @@
+use crate::{InsertError, Match, MethodError, Router};
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// A URL router that stores a value for each HTTP method of a route.
+///
+/// Matching distinguishes between paths without any route, which fail with
+/// [`MethodError::NotFound`], and routes without a value for the request method, which
+/// fail with [`MethodError::NotAllowed`] along with the value of the `Allow` header to
+/// respond with.
+///
+/// `HEAD` requests fall back to the value for `GET`, and `OPTIONS` requests without
+/// an explicit value fail with [`MethodError::Options`] so that they can be answered
+/// with the allowed methods.
+///
+/// ```rust
+/// use matchit::{MethodError, MethodRouter};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut router = MethodRouter::new();
+/// router.insert("GET", "/users/:id", "Get a User")?;
+/// router.insert("DELETE", "/users/:id", "Delete a User")?;
+///
+/// let matched = router.at("DELETE", "/users/978").unwrap();
+/// assert_eq!(matched.params.get("id"), Some("978"));
+/// assert_eq!(*matched.value, "Delete a User");
+///
+/// // `HEAD` falls back to `GET`
+/// assert_eq!(*router.at("HEAD", "/users/978").unwrap().value, "Get a User");
+///
+/// assert_eq!(
+///     router.at("POST", "/users/978").unwrap_err(),
+///     MethodError::NotAllowed { allow: "GET, HEAD, DELETE, OPTIONS" }
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub struct MethodRouter<T> {
+    router: Router<Methods<T>>,
+}
+
+// The values of a route for each method, in the order they were inserted.
+struct Methods<T> {
+    values: Vec<(Box<str>, T)>,
+    // the value of the `Allow` header for the route
+    allow: String,
+}
+
+impl<T> Methods<T> {
+    fn new(method: &str, value: T) -> Self {
+        let mut methods = Methods {
+            values: Vec::new(),
+            allow: String::new(),
+        };
+
+        methods.push(method, value);
+        methods
+    }
+
+    fn push(&mut self, method: &str, value: T) {
+        self.values.push((method.into(), value));
+
+        let implicit_head = self.get("GET").is_some() && self.get("HEAD").is_none();
+        let implicit_options = self.get("OPTIONS").is_none();
+
+        self.allow.clear();
+        for (method, _) in &self.values {
+            if !self.allow.is_empty() {
+                self.allow.push_str(", ");
+            }
+
+            self.allow.push_str(method);
+
+            if implicit_head && &**method == "GET" {
+                self.allow.push_str(", HEAD");
+            }
+        }
+
+        if implicit_options {
+            self.allow.push_str(", OPTIONS");
+        }
+    }
+
+    fn get(&self, method: &str) -> Option<usize> {
+        self.values.iter().position(|(m, _)| &**m == method)
+    }
+
+    // Returns the index of the value for the method, falling back from `HEAD` to `GET`.
+    fn find(&self, method: &str) -> Result<usize, MethodError<'_>> {
+        if let Some(i) = self.get(method) {
+            return Ok(i);
+        }
+
+        match method {
+            "HEAD" => self
+                .get("GET")
+                .ok_or(MethodError::NotAllowed { allow: &self.allow }),
+            "OPTIONS" => Err(MethodError::Options { allow: &self.allow }),
+            _ => Err(MethodError::NotAllowed { allow: &self.allow }),
+        }
+    }
+}
+
+impl<T> Default for MethodRouter<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> MethodRouter<T> {
+    /// Construct a new router.
+    pub const fn new() -> Self {
+        Self {
+            router: Router::new(),
+        }
+    }
+
+    /// Insert a route for the given method into the router.
+    ///
+    /// Methods are case-sensitive, as in HTTP. Inserting the same route and method twice
+    /// fails with [`InsertError::Conflict`].
+    ///
+    /// ```rust
+    /// # use matchit::MethodRouter;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut router = MethodRouter::new();
+    /// router.insert("GET", "/home", "Welcome!")?;
+    /// router.insert("POST", "/home", "Hello!")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn insert(
+        &mut self,
+        method: &str,
+        route: impl Into<String>,
+        value: T,
+    ) -> Result<(), InsertError> {
+        let route = route.into();
+
+        if let Some(methods) = self.router.route_mut(&route) {
+            if methods.get(method).is_some() {
+                return Err(InsertError::Conflict { with: route });
+            }
+
+            methods.push(method, value);
+            return Ok(());
+        }
+
+        self.router.insert(route, Methods::new(method, value))
+    }
+
+    /// Tries to find a value in the router matching the given method and path.
+    ///
+    /// See [`MethodRouter`] for how `HEAD` and `OPTIONS` requests are handled.
+    pub fn at<'m, 'p>(
+        &'m self,
+        method: &str,
+        path: &'p str,
+    ) -> Result<Match<'m, 'p, &'m T>, MethodError<'m>> {
+        let matched = self.router.at(path).map_err(MethodError::NotFound)?;
+        let i = matched.value.find(method)?;
+
+        Ok(Match {
+            value: &matched.value.values[i].1,
+            params: matched.params,
+        })
+    }
+
+    /// Tries to find a value in the router matching the given method and path,
+    /// returning a mutable reference.
+    ///
+    /// See [`MethodRouter`] for how `HEAD` and `OPTIONS` requests are handled.
+    pub fn at_mut<'m, 'p>(
+        &'m mut self,
+        method: &str,
+        path: &'p str,
+    ) -> Result<Match<'m, 'p, &'m mut T>, MethodError<'m>> {
+        let matched = self.router.at_mut(path).map_err(MethodError::NotFound)?;
+        let methods = matched.value;
+
+        match methods.find(method) {
+            Ok(i) => Ok(Match {
+                value: &mut methods.values[i].1,
+                params: matched.params,
+            }),
+            Err(MethodError::NotAllowed { .. }) => Err(MethodError::NotAllowed {
+                allow: &methods.allow,
+            }),
+            Err(_) => Err(MethodError::Options {
+                allow: &methods.allow,
+            }),
+        }
+    }
+}
@@ -1,5 +1,8 @@
+use alloc::borrow::ToOwned;
 use alloc::str;
+use alloc::string::String;
 use alloc::vec::Vec;
+use core::cmp::Ordering;
 use core::iter;
 use core::mem;
 use core::slice;
@@ -11,13 +14,14 @@ struct Param<'k, 'v> {
 }
 
 impl<'k, 'v> Param<'k, 'v> {
-    // this could be from_utf8_unchecked, but we'll keep this safe for now
     fn key_str(&self) -> &'k str {
-        str::from_utf8(self.key).unwrap()
+        // SAFETY: keys are always valid UTF-8, see `Params`
+        unsafe { str::from_utf8_unchecked(self.key) }
     }
 
     fn value_str(&self) -> &'v str {
-        str::from_utf8(self.value).unwrap()
+        // SAFETY: the values of a `Params` are valid UTF-8, see `Params`
+        unsafe { str::from_utf8_unchecked(self.value) }
     }
 }
 
@@ -40,8 +44,12 @@ impl<'k, 'v> Param<'k, 'v> {
 /// # Ok(())
 /// # }
 /// ```
-#[derive(Debug, PartialEq, Eq, Ord, PartialOrd, Clone)]
+#[derive(Debug, Clone)]
 pub struct Params<'k, 'v> {
+    // The keys and values are always valid UTF-8. Keys are the names of parameters in
+    // routes, which are strings, and values are split from a string path at the bytes of an
+    // ASCII syntax, which are character boundaries. Parameters matched against raw bytes
+    // are only exposed through `BytesParams`.
     kind: ParamsKind<'k, 'v>,
 }
 
@@ -53,20 +61,102 @@ enum ParamsKind<'k, 'v> {
     None,
     Small([Param<'k, 'v>; SMALL], usize),
     Large(Vec<Param<'k, 'v>>),
+    // parameters stored in a `ParamsBuffer` while matching, with values in the given path
+    Buffered(Vec<Offsets<'k>>, &'v [u8]),
+    // parameters borrowed from a `ParamsBuffer` after matching
+    Borrowed(&'k [Offsets<'k>], &'v [u8]),
 }
 
+// A parameter whose value is stored as a range of the path it was matched against, so that
+// it can be kept in a buffer that outlives the path.
+#[derive(Debug, PartialEq, Eq, Ord, PartialOrd, Copy, Clone)]
+struct Offsets<'k> {
+    key: &'k [u8],
+    start: usize,
+    end: usize,
+}
+
+impl<'k> Offsets<'k> {
+    fn param<'v>(&self, path: &'v [u8]) -> Param<'k, 'v> {
+        Param {
+            key: self.key,
+            value: &path[self.start..self.end],
+        }
+    }
+}
+
+/// A buffer that parameters can be stored in across matches, see [`MatchScratch`](crate::MatchScratch).
+#[derive(Default)]
+pub(crate) struct ParamsBuffer<'k>(Vec<Offsets<'k>>);
+
 impl<'k, 'v> Params<'k, 'v> {
     pub(crate) fn new() -> Self {
         let kind = ParamsKind::None;
         Self { kind }
     }
 
+    // Creates an empty list that stores parameters matched against `path` in the given
+    // buffer, so that pushing parameters only allocates if the buffer runs out of capacity.
+    //
+    // Every value pushed to the list must be a part of `path`.
+    pub(crate) fn with_buffer(buffer: &mut ParamsBuffer<'k>, path: &'v [u8]) -> Self {
+        let mut vec = mem::take(&mut buffer.0);
+        vec.clear();
+
+        let kind = ParamsKind::Buffered(vec, path);
+        Self { kind }
+    }
+
+    // Moves the parameters of a list created with `with_buffer` back into the buffer,
+    // returning a list that borrows them.
+    pub(crate) fn into_buffer<'b>(self, buffer: &'b mut ParamsBuffer<'k>) -> Params<'b, 'b>
+    where
+        'k: 'b,
+        'v: 'b,
+    {
+        let (vec, path) = match self.kind {
+            ParamsKind::Buffered(vec, path) => (vec, path),
+            kind => return Params { kind },
+        };
+
+        // copy small lists so that they don't borrow the buffer
+        if vec.len() <= SMALL {
+            let mut arr = [Param::default(); SMALL];
+            for (param, offsets) in arr.iter_mut().zip(&vec) {
+                *param = offsets.param(path);
+            }
+
+            let kind = ParamsKind::Small(arr, vec.len());
+            buffer.0 = vec;
+            return Params { kind };
+        }
+
+        buffer.0 = vec;
+        let kind = ParamsKind::Borrowed(&buffer.0, path);
+        Params { kind }
+    }
+
+    // Returns an iterator over the parameters in the list.
+    fn params(&self) -> impl Iterator<Item = Param<'k, 'v>> + '_ {
+        let mut iter = ParamsIter::new(self);
+        iter::from_fn(move || iter.next_param())
+    }
+
+    // Copies borrowed parameters, so that the list can be modified.
+    fn make_owned(&mut self) {
+        if let ParamsKind::Borrowed(..) = self.kind {
+            self.kind = ParamsKind::Large(self.params().collect());
+        }
+    }
+
     /// Returns the number of parameters.
     pub fn len(&self) -> usize {
         match &self.kind {
             ParamsKind::None => 0,
             ParamsKind::Small(_, len) => *len,
             ParamsKind::Large(vec) => vec.len(),
+            ParamsKind::Buffered(vec, _) => vec.len(),
+            ParamsKind::Borrowed(slice, _) => slice.len(),
         }
     }
 
@@ -79,25 +169,27 @@ impl<'k, 'v> Params<'k, 'v> {
             ParamsKind::Large(vec) => {
                 vec.truncate(n);
             }
+            ParamsKind::Buffered(vec, _) => {
+                vec.truncate(n);
+            }
+            ParamsKind::Borrowed(slice, _) => {
+                *slice = &slice[..n];
+            }
         }
     }
 
     /// Returns the value of the first parameter registered under the given key.
     pub fn get(&self, key: impl AsRef<str>) -> Option<&'v str> {
-        let key = key.as_ref().as_bytes();
+        self.find(key.as_ref()).map(|param| param.value_str())
+    }
 
-        match &self.kind {
-            ParamsKind::None => None,
-            ParamsKind::Small(arr, len) => arr
-                .iter()
-                .take(*len)
-                .find(|param| param.key == key)
-                .map(Param::value_str),
-            ParamsKind::Large(vec) => vec
-                .iter()
-                .find(|param| param.key == key)
-                .map(Param::value_str),
-        }
+    /// Returns the value of the first parameter registered under the given key as raw bytes.
+    pub fn get_bytes(&self, key: impl AsRef<str>) -> Option<&'v [u8]> {
+        self.find(key.as_ref()).map(|param| param.value)
+    }
+
+    fn find(&self, key: &str) -> Option<Param<'k, 'v>> {
+        self.params().find(|param| param.key == key.as_bytes())
     }
 
     /// Returns an iterator over the parameters in the list.
@@ -105,16 +197,25 @@ impl<'k, 'v> Params<'k, 'v> {
         ParamsIter::new(self)
     }
 
+    /// Returns an iterator over the parameters in the list, with values as raw bytes.
+    pub fn iter_bytes(&self) -> impl Iterator<Item = (&'k str, &'v [u8])> + '_ {
+        self.params().map(|param| (param.key_str(), param.value))
+    }
+
     /// Returns `true` if there are no parameters in the list.
     pub fn is_empty(&self) -> bool {
         match &self.kind {
             ParamsKind::None => true,
             ParamsKind::Small(_, len) => *len == 0,
             ParamsKind::Large(vec) => vec.is_empty(),
+            ParamsKind::Buffered(vec, _) => vec.is_empty(),
+            ParamsKind::Borrowed(slice, _) => slice.is_empty(),
         }
     }
 
     /// Inserts a key value parameter pair into the list.
+    ///
+    /// Both must be valid UTF-8, unless the list is only exposed as `BytesParams`.
     pub(crate) fn push(&mut self, key: &'k [u8], value: &'v [u8]) {
         #[cold]
         fn drain_to_vec<T: Default>(len: usize, elem: T, arr: &mut [T; SMALL]) -> Vec<T> {
@@ -124,6 +225,8 @@ impl<'k, 'v> Params<'k, 'v> {
             vec
         }
 
+        self.make_owned();
+
         let param = Param { key, value };
         match &mut self.kind {
             ParamsKind::None => {
@@ -138,11 +241,22 @@ impl<'k, 'v> Params<'k, 'v> {
                 *len += 1;
             }
             ParamsKind::Large(vec) => vec.push(param),
+            ParamsKind::Buffered(vec, path) => {
+                let start = value.as_ptr() as usize - path.as_ptr() as usize;
+                vec.push(Offsets {
+                    key,
+                    start,
+                    end: start + value.len(),
+                });
+            }
+            ParamsKind::Borrowed(..) => unreachable!(),
         }
     }
 
     // Transform each key.
     pub(crate) fn for_each_key_mut(&mut self, f: impl Fn((usize, &mut &'k [u8]))) {
+        self.make_owned();
+
         match &mut self.kind {
             ParamsKind::None => {}
             ParamsKind::Small(arr, len) => arr
@@ -156,10 +270,87 @@ impl<'k, 'v> Params<'k, 'v> {
                 .map(|param| &mut param.key)
                 .enumerate()
                 .for_each(f),
+            ParamsKind::Buffered(vec, _) => vec
+                .iter_mut()
+                .map(|param| &mut param.key)
+                .enumerate()
+                .for_each(f),
+            ParamsKind::Borrowed(..) => unreachable!(),
         }
     }
 }
 
+impl PartialEq for Params<'_, '_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.params().eq(other.params())
+    }
+}
+
+impl Eq for Params<'_, '_> {}
+
+impl PartialOrd for Params<'_, '_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Params<'_, '_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.params().cmp(other.params())
+    }
+}
+
+/// A list of parameters matched against a path of raw bytes, see
+/// [`Router::at_bytes`](crate::Router::at_bytes).
+///
+/// Unlike [`Params`], the values don't have to be valid UTF-8, so they are only accessible
+/// as bytes.
+///
+/// ```rust
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # let mut router = matchit::Router::new();
+/// # router.insert("/files/:name", true).unwrap();
+/// let matched = router.at_bytes(b"/files/caf\xe9")?;
+///
+/// assert_eq!(matched.params.get("name"), Some(&b"caf\xe9"[..]));
+/// for (key, value) in matched.params.iter() {
+///     println!("key: {}, value: {:?}", key, value);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct BytesParams<'k, 'v> {
+    // the keys are valid UTF-8, but the values may not be
+    params: Params<'k, 'v>,
+}
+
+impl<'k, 'v> BytesParams<'k, 'v> {
+    pub(crate) fn new(params: Params<'k, 'v>) -> Self {
+        Self { params }
+    }
+
+    /// Returns the number of parameters.
+    pub fn len(&self) -> usize {
+        self.params.len()
+    }
+
+    /// Returns `true` if there are no parameters in the list.
+    pub fn is_empty(&self) -> bool {
+        self.params.is_empty()
+    }
+
+    /// Returns the value of the first parameter registered under the given key.
+    pub fn get(&self, key: impl AsRef<str>) -> Option<&'v [u8]> {
+        self.params.get_bytes(key)
+    }
+
+    /// Returns an iterator over the parameters in the list.
+    pub fn iter(&self) -> impl Iterator<Item = (&'k str, &'v [u8])> + '_ {
+        self.params.iter_bytes()
+    }
+}
+
 /// An iterator over the keys and values of a route's [parameters](crate::Params).
 pub struct ParamsIter<'ps, 'k, 'v> {
     kind: ParamsIterKind<'ps, 'k, 'v>,
@@ -171,29 +362,100 @@ impl<'ps, 'k, 'v> ParamsIter<'ps, 'k, 'v> {
             ParamsKind::None => ParamsIterKind::None,
             ParamsKind::Small(arr, len) => ParamsIterKind::Small(arr.iter().take(*len)),
             ParamsKind::Large(vec) => ParamsIterKind::Large(vec.iter()),
+            ParamsKind::Buffered(vec, path) => ParamsIterKind::Offsets(vec.iter(), path),
+            ParamsKind::Borrowed(slice, path) => ParamsIterKind::Offsets(slice.iter(), path),
         };
         Self { kind }
     }
+
+    fn next_param(&mut self) -> Option<Param<'k, 'v>> {
+        match self.kind {
+            ParamsIterKind::None => None,
+            ParamsIterKind::Small(ref mut iter) => iter.next().copied(),
+            ParamsIterKind::Large(ref mut iter) => iter.next().copied(),
+            ParamsIterKind::Offsets(ref mut iter, path) => iter.next().map(|o| o.param(path)),
+        }
+    }
 }
 
 enum ParamsIterKind<'ps, 'k, 'v> {
     None,
     Small(iter::Take<slice::Iter<'ps, Param<'k, 'v>>>),
     Large(slice::Iter<'ps, Param<'k, 'v>>),
+    Offsets(slice::Iter<'ps, Offsets<'k>>, &'v [u8]),
 }
 
 impl<'ps, 'k, 'v> Iterator for ParamsIter<'ps, 'k, 'v> {
     type Item = (&'k str, &'v str);
 
     fn next(&mut self) -> Option<Self::Item> {
-        match self.kind {
-            ParamsIterKind::None => None,
-            ParamsIterKind::Small(ref mut iter) => {
-                iter.next().map(|p| (p.key_str(), p.value_str()))
-            }
-            ParamsIterKind::Large(ref mut iter) => {
-                iter.next().map(|p| (p.key_str(), p.value_str()))
-            }
+        let param = self.next_param()?;
+        Some((param.key_str(), param.value_str()))
+    }
+}
+
+/// An owned list of parameters, created from [`Params`].
+///
+/// This is useful when parameters need to outlive the path they were matched against,
+/// for example to store them in the extensions of a request.
+///
+/// ```rust
+/// use matchit::{OwnedParams, Router};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut router = Router::new();
+/// router.insert("/users/:id", true)?;
+///
+/// let params = OwnedParams::from(router.at(&String::from("/users/1"))?.params);
+/// assert_eq!(params.get("id"), Some("1"));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct OwnedParams {
+    params: Vec<(String, String)>,
+}
+
+impl OwnedParams {
+    /// Returns the number of parameters.
+    pub fn len(&self) -> usize {
+        self.params.len()
+    }
+
+    /// Returns the value of the first parameter registered under the given key.
+    pub fn get(&self, key: impl AsRef<str>) -> Option<&str> {
+        let key = key.as_ref();
+
+        self.params
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Returns an iterator over the parameters in the list.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.params.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    /// Returns `true` if there are no parameters in the list.
+    pub fn is_empty(&self) -> bool {
+        self.params.is_empty()
+    }
+}
+
+impl From<Params<'_, '_>> for OwnedParams {
+    fn from(params: Params<'_, '_>) -> Self {
+        OwnedParams::from(&params)
+    }
+}
+
+impl From<&Params<'_, '_>> for OwnedParams {
+    fn from(params: &Params<'_, '_>) -> Self {
+        OwnedParams {
+            params: params
+                .iter()
+                .map(|(k, v)| (k.to_owned(), v.to_owned()))
+                .collect(),
         }
     }
 }
@@ -249,6 +511,21 @@ mod tests {
         assert!(params.iter().eq(vec.clone()));
     }
 
+    #[test]
+    fn bytes() {
+        let mut params = Params::new();
+        params.push(b"a", b"\xff");
+        params.push(b"b", "é".as_bytes());
+
+        let params = BytesParams::new(params);
+        assert_eq!(params.len(), 2);
+        assert_eq!(params.get("a"), Some(&b"\xff"[..]));
+        assert_eq!(params.get("b"), Some("é".as_bytes()));
+        assert!(params
+            .iter()
+            .eq([("a", &b"\xff"[..]), ("b", "é".as_bytes())]));
+    }
+
     #[test]
     fn ignore_array_default() {
         let params = Params::new();
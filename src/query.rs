@@ -0,0 +1,220 @@
+use crate::{InsertError, Match, MatchError, Params, Router};
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cell::UnsafeCell;
+
+/// A URL router whose routes can require query parameters.
+///
+/// Routes are paths followed by the names of the query parameters they require, like
+/// `/search?q&page`. Routes with the same path are selected by their query parameters,
+/// with routes requiring more parameters tried first. If the query is missing parameters
+/// required by every route of the path, the next route matching the path is tried, in
+/// the same order as [`Router::matches`], before failing with [`MatchError::NotFound`].
+///
+/// The values of the required query parameters are returned along with the route
+/// parameters, after them. Like route parameters, query values are not percent-decoded, and
+/// the first value is used if a parameter is repeated.
+///
+/// ```rust
+/// use matchit::QueryRouter;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut router = QueryRouter::new();
+/// router.insert("/search?q&page", "Search Page")?;
+/// router.insert("/search?q", "Search")?;
+/// router.insert("/search", "Search Form")?;
+///
+/// let matched = router.at("/search?q=rust&page=2")?;
+/// assert_eq!(*matched.value, "Search Page");
+/// assert_eq!(matched.params.get("page"), Some("2"));
+///
+/// let matched = router.at("/search?q=rust#results")?;
+/// assert_eq!(*matched.value, "Search");
+/// assert_eq!(matched.params.get("q"), Some("rust"));
+///
+/// assert_eq!(*router.at("/search")?.value, "Search Form");
+/// # Ok(())
+/// # }
+/// ```
+pub struct QueryRouter<T> {
+    router: Router<Queries<T>>,
+}
+
+// The values of a path for each set of required query parameters.
+struct Queries<T> {
+    // ordered by the number of required parameters, most first
+    values: Vec<(Keys, UnsafeCell<T>)>,
+}
+
+// The names of the query parameters required by a route.
+type Keys = Box<[Box<str>]>;
+
+impl<T> Default for QueryRouter<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> QueryRouter<T> {
+    /// Construct a new router.
+    pub const fn new() -> Self {
+        Self {
+            router: Router::new(),
+        }
+    }
+
+    /// Insert a route into the router.
+    ///
+    /// The required query parameters of a route are separated by `&`, and their order does
+    /// not matter. Inserting a path with the same query parameters twice fails with
+    /// [`InsertError::Conflict`].
+    ///
+    /// ```rust
+    /// # use matchit::QueryRouter;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut router = QueryRouter::new();
+    /// router.insert("/users/:id", "A User")?;
+    /// router.insert("/users/:id?fields", "Some Fields of a User")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn insert(&mut self, route: impl Into<String>, value: T) -> Result<(), InsertError> {
+        let mut path = route.into();
+
+        let keys = match path.find('?') {
+            Some(i) => {
+                let keys = parse_keys(&path[i + 1..])?;
+                path.truncate(i);
+                keys
+            }
+            None => Box::default(),
+        };
+
+        let queries = match self.router.route_mut(&path) {
+            Some(queries) => queries,
+            None => {
+                let values = Vec::from([(keys, UnsafeCell::new(value))]);
+                return self.router.insert(path, Queries { values });
+            }
+        };
+
+        if let Some((existing, _)) = queries.values.iter().find(|(k, _)| same_keys(k, &keys)) {
+            let mut with = path;
+            if !existing.is_empty() {
+                with.push('?');
+                with.push_str(&existing.join("&"));
+            }
+
+            return Err(InsertError::Conflict { with });
+        }
+
+        let i = queries
+            .values
+            .iter()
+            .position(|(k, _)| k.len() < keys.len())
+            .unwrap_or(queries.values.len());
+        queries.values.insert(i, (keys, UnsafeCell::new(value)));
+
+        Ok(())
+    }
+
+    /// Tries to find a value in the router matching the given path and query, ignoring
+    /// any fragment.
+    pub fn at<'m, 'p>(&'m self, uri: &'p str) -> Result<Match<'m, 'p, &'m T>, MatchError> {
+        let (value, params) = self.find(uri)?;
+
+        Ok(Match {
+            // SAFETY: We only expose &mut T through &mut self
+            value: unsafe { &*value },
+            params,
+        })
+    }
+
+    /// Tries to find a value in the router matching the given path and query, ignoring
+    /// any fragment, returning a mutable reference.
+    pub fn at_mut<'m, 'p>(
+        &'m mut self,
+        uri: &'p str,
+    ) -> Result<Match<'m, 'p, &'m mut T>, MatchError> {
+        let (value, params) = self.find(uri)?;
+
+        Ok(Match {
+            // SAFETY: We have &mut self
+            value: unsafe { &mut *value },
+            params,
+        })
+    }
+
+    fn find<'m, 'p>(&'m self, uri: &'p str) -> Result<(*mut T, Params<'m, 'p>), MatchError> {
+        let (path, query) = split_uri(uri);
+        let query = query.unwrap_or_default();
+
+        // try every route matching the path in priority order, until one of them has all of
+        // its required query parameters
+        for matched in self.router.matches(path) {
+            let mut params = matched.params;
+
+            for (keys, value) in &matched.value.values {
+                if keys.iter().all(|key| query_value(query, key).is_some()) {
+                    for key in keys.iter() {
+                        let value = query_value(query, key).unwrap();
+                        params.push(key.as_bytes(), value.as_bytes());
+                    }
+
+                    return Ok((value.get(), params));
+                }
+            }
+        }
+
+        // recommend a trailing slash if no route matches the path
+        match self.router.find(path.as_bytes()) {
+            Ok(_) => Err(MatchError::NotFound),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+// Splits a URI into its path and query, ignoring the fragment.
+pub(crate) fn split_uri(uri: &str) -> (&str, Option<&str>) {
+    let uri = uri.split_once('#').map_or(uri, |(uri, _)| uri);
+
+    match uri.split_once('?') {
+        Some((path, query)) => (path, Some(query)),
+        None => (uri, None),
+    }
+}
+
+// Returns the value of the first query parameter with the given name.
+fn query_value<'q>(query: &'q str, key: &str) -> Option<&'q str> {
+    query.split('&').find_map(|pair| {
+        let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+        if name == key {
+            Some(value)
+        } else {
+            None
+        }
+    })
+}
+
+// Parses the names of the required query parameters of a route.
+fn parse_keys(query: &str) -> Result<Keys, InsertError> {
+    let mut keys = Vec::<Box<str>>::new();
+
+    for key in query.split('&') {
+        if key.is_empty()
+            || key.contains(|c| matches!(c, '=' | '?' | '#'))
+            || keys.iter().any(|k| **k == *key)
+        {
+            return Err(InsertError::InvalidRoute);
+        }
+
+        keys.push(key.into());
+    }
+
+    Ok(keys.into())
+}
+
+fn same_keys(a: &[Box<str>], b: &[Box<str>]) -> bool {
+    a.len() == b.len() && a.iter().all(|key| b.contains(key))
+}
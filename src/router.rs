@@ -1,32 +1,158 @@
-use crate::tree::Node;
-use crate::{InsertError, MatchError, Params};
+use crate::index::StaticIndex;
+use crate::query;
+use crate::static_router;
+use crate::syntax::{Path, Syntax};
+use crate::tree::{self, Entry, Node};
+use crate::{BytesParams, FrozenRouter, InsertError, MatchError, MatchScratch, MergeError, Params};
 use alloc::string::String;
+use alloc::vec::Vec;
 
 /// A URL router.
 ///
 /// See [the crate documentation](crate) for details.
-#[derive(Clone)]
+///
+/// Routes are URL paths by default, but any hierarchical key can be routed with a
+/// different [`Syntax`], see [`Router::with_syntax`]. Routes can also carry metadata of
+/// type `M` alongside their values, see [`Router::with_meta`] and
+/// [`Router::with_syntax_and_meta`].
+///
+/// With the `serde` feature enabled, a built router can be serialized and loaded again
+/// without inserting every route. Loading checks that the tree is well-formed, so routers
+/// from untrusted sources can be deserialized safely.
 #[cfg_attr(test, derive(Debug))]
-pub struct Router<T> {
-    root: Node<T>,
+pub struct Router<T, S = Path, M = ()> {
+    pub(crate) root: Node<T, S, M>,
+    // routes without parameters, which are matched before walking the tree
+    pub(crate) statics: StaticIndex<T, M>,
+    pub(crate) validate: bool,
 }
 
 impl<T> Default for Router<T> {
     fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone, S: Syntax, M: Clone> Clone for Router<T, S, M> {
+    fn clone(&self) -> Self {
+        let root = self.root.clone();
         Self {
-            root: Node::default(),
+            statics: StaticIndex::build(&root),
+            root,
+            validate: self.validate,
         }
     }
 }
 
 impl<T> Router<T> {
     /// Construct a new router.
-    pub fn new() -> Self {
-        Self::default()
+    ///
+    /// This is a `const fn`, so an empty router can be used to initialize a `static`.
+    pub const fn new() -> Self {
+        Self {
+            root: Node::new(),
+            statics: StaticIndex::new(),
+            validate: true,
+        }
+    }
+}
+
+impl Router<()> {
+    /// Checks that a route is well-formed, without inserting it.
+    ///
+    /// Routes must start with `/` and only contain characters that are valid in a URL path,
+    /// as defined by [RFC 3986](https://datatracker.ietf.org/doc/html/rfc3986#section-3.3).
+    /// Characters such as spaces, `?` or `#` would make a route impossible to match. A route
+    /// can have at most 26 named parameters.
+    ///
+    /// Note that this does not check for conflicts with existing routes.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use matchit::{InsertError, Router};
+    /// assert_eq!(Router::validate("/users/:id"), Ok(()));
+    /// assert_eq!(Router::validate("users/:id"), Err(InsertError::InvalidRoute));
+    /// assert_eq!(Router::validate("/search?q"), Err(InsertError::InvalidRoute));
+    /// assert_eq!(Router::validate("/files/*path/x"), Err(InsertError::InvalidCatchAll));
+    /// ```
+    pub fn validate(route: &str) -> Result<(), InsertError> {
+        tree::validate_route(route.as_bytes())
+    }
+}
+
+impl<T, M> Router<T, Path, M> {
+    /// Construct a new router whose routes carry metadata of type `M`.
+    ///
+    /// See [`Router::insert_with_meta`] for details. Use [`Router::with_syntax_and_meta`]
+    /// for metadata on routes of another [`Syntax`].
+    pub const fn with_meta() -> Self {
+        Self {
+            root: Node::new(),
+            statics: StaticIndex::new(),
+            validate: true,
+        }
     }
+}
+
+impl<T, S> Router<T, S> {
+    /// Construct a new router for routes in the [`Syntax`] `S`.
+    ///
+    /// Use [`Router::with_syntax_and_meta`] for routes that carry metadata.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use matchit::{Path, Router};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut router = Router::<_, Path>::with_syntax();
+    /// router.insert("/home", "Welcome!")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub const fn with_syntax() -> Self {
+        Self {
+            root: Node::new(),
+            statics: StaticIndex::new(),
+            validate: true,
+        }
+    }
+}
 
+impl<T, S, M> Router<T, S, M> {
+    /// Construct a new router for routes in the [`Syntax`] `S`, which carry metadata of
+    /// type `M`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use matchit::{Path, Router};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut router = Router::<_, Path, _>::with_syntax_and_meta();
+    /// router.insert_with_meta("/home", "Welcome!", "getHome")?;
+    ///
+    /// let (matched, meta) = router.at_meta("/home")?;
+    /// assert_eq!(*matched.value, "Welcome!");
+    /// assert_eq!(*meta, "getHome");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub const fn with_syntax_and_meta() -> Self {
+        Self {
+            root: Node::new(),
+            statics: StaticIndex::new(),
+            validate: true,
+        }
+    }
+}
+
+impl<T, S: Syntax, M> Router<T, S, M> {
     /// Insert a route.
     ///
+    /// Unless disabled with [`Router::set_validation`], the route is checked with
+    /// [`Syntax::validate`] before it is inserted, which is [`Router::validate`] for
+    /// URL paths.
+    ///
     /// # Examples
     ///
     /// ```rust
@@ -38,8 +164,93 @@ impl<T> Router<T> {
     /// # Ok(())
     /// # }
     /// ```
-    pub fn insert(&mut self, route: impl Into<String>, value: T) -> Result<(), InsertError> {
-        self.root.insert(route, value)
+    pub fn insert(&mut self, route: impl Into<String>, value: T) -> Result<(), InsertError>
+    where
+        M: Default,
+    {
+        self.insert_with_meta(route, value, M::default())
+    }
+
+    /// Insert a route along with its metadata.
+    ///
+    /// Metadata is kept separate from the value of a route, and is returned along with it
+    /// by [`Router::at_meta`] and [`Router::routes`]. Routes inserted with
+    /// [`Router::insert`] have the default metadata.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use matchit::Router;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// #[derive(Default)]
+    /// struct Meta {
+    ///     auth: bool,
+    ///     operation_id: &'static str,
+    /// }
+    ///
+    /// let mut router = Router::with_meta();
+    /// router.insert("/health", "Health Check")?;
+    /// router.insert_with_meta(
+    ///     "/users/:id",
+    ///     "A User",
+    ///     Meta { auth: true, operation_id: "getUser" },
+    /// )?;
+    ///
+    /// let (matched, meta) = router.at_meta("/users/978")?;
+    /// assert_eq!(*matched.value, "A User");
+    /// assert!(meta.auth);
+    /// assert_eq!(meta.operation_id, "getUser");
+    ///
+    /// assert!(!router.at_meta("/health")?.1.auth);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn insert_with_meta(
+        &mut self,
+        route: impl Into<String>,
+        value: T,
+        meta: M,
+    ) -> Result<(), InsertError> {
+        let route = route.into();
+
+        if self.validate {
+            S::validate(route.as_bytes())?;
+        }
+
+        if route.bytes().any(|b| b == S::PARAM || b == S::CATCH_ALL) {
+            self.root.insert_with_meta(route, value, meta)?;
+        } else {
+            let entry = self.root.insert_with_meta(route.as_str(), value, meta)?;
+            self.statics.insert(route.as_bytes(), entry);
+        }
+
+        Ok(())
+    }
+
+    /// Enables or disables route validation on insertion, which is enabled by default.
+    ///
+    /// See [`Router::validate`] for details.
+    ///
+    /// Validating routes by default is a breaking change: earlier versions inserted any
+    /// route, while routes like `users` or `/a b` now fail with [`InsertError::InvalidRoute`].
+    /// Disabling validation restores the old behavior.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use matchit::Router;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut router = Router::new();
+    /// assert!(router.insert("/hello world", true).is_err());
+    ///
+    /// router.set_validation(false);
+    /// router.insert("/hello world", true)?;
+    /// assert!(router.at("/hello world").is_ok());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_validation(&mut self, enabled: bool) {
+        self.validate = enabled;
     }
 
     /// Tries to find a value in the router matching the given path.
@@ -58,14 +269,47 @@ impl<T> Router<T> {
     /// # }
     /// ```
     pub fn at<'m, 'p>(&'m self, path: &'p str) -> Result<Match<'m, 'p, &'m T>, MatchError> {
-        match self.root.at(path.as_bytes()) {
-            Ok((value, params)) => Ok(Match {
-                // SAFETY: We only expose &mut T through &mut self
-                value: unsafe { &*value.get() },
-                params,
-            }),
-            Err(e) => Err(e),
-        }
+        let (value, params) = self.find(path.as_bytes())?;
+
+        Ok(Match {
+            // SAFETY: We only expose &mut T through &mut self
+            value: unsafe { &*value.value.get() },
+            params,
+        })
+    }
+
+    /// Tries to find a value in the router matching the given path, along with the
+    /// metadata of its route.
+    ///
+    /// See [`Router::insert_with_meta`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use matchit::Router;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut router = Router::with_meta();
+    /// router.insert_with_meta("/users/:id", "A User", "getUser")?;
+    ///
+    /// let (matched, meta) = router.at_meta("/users/978")?;
+    /// assert_eq!(*matched.value, "A User");
+    /// assert_eq!(*meta, "getUser");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn at_meta<'m, 'p>(
+        &'m self,
+        path: &'p str,
+    ) -> Result<(Match<'m, 'p, &'m T>, &'m M), MatchError> {
+        let (value, params) = self.find(path.as_bytes())?;
+
+        let matched = Match {
+            // SAFETY: We only expose &mut T through &mut self
+            value: unsafe { &*value.value.get() },
+            params,
+        };
+
+        Ok((matched, &value.meta))
     }
 
     /// Tries to find a value in the router matching the given path,
@@ -88,22 +332,553 @@ impl<T> Router<T> {
         &'m mut self,
         path: &'p str,
     ) -> Result<Match<'m, 'p, &'m mut T>, MatchError> {
-        match self.root.at(path.as_bytes()) {
+        let (value, params) = self.find(path.as_bytes())?;
+
+        Ok(Match {
+            // SAFETY: We have &mut self
+            value: unsafe { &mut *value.value.get() },
+            params,
+        })
+    }
+
+    /// Tries to find a value in the router matching the given path as raw bytes, which
+    /// don't have to be valid UTF-8.
+    ///
+    /// As the parameters may not be valid UTF-8 either, they are returned as
+    /// [`BytesParams`], which only gives access to them as bytes. They are kept apart from
+    /// [`Params`] so that its `&str` accessors never have to check values or fail, while
+    /// [`Params::get_bytes`] gives the same access to the parameters of [`Router::at`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use matchit::Router;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut router = Router::new();
+    /// router.insert("/files/:name", "A File")?;
+    ///
+    /// let matched = router.at_bytes(b"/files/caf\xe9")?;
+    /// assert_eq!(matched.params.get("name"), Some(&b"caf\xe9"[..]));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn at_bytes<'m, 'p>(
+        &'m self,
+        path: &'p [u8],
+    ) -> Result<BytesMatch<'m, 'p, &'m T>, MatchError> {
+        let (value, params) = self.find(path)?;
+
+        Ok(BytesMatch {
+            // SAFETY: We only expose &mut T through &mut self
+            value: unsafe { &*value.value.get() },
+            params: BytesParams::new(params),
+        })
+    }
+
+    /// Returns an iterator over all routes in the router, along with their values and
+    /// metadata, in priority order.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use matchit::Router;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut router = Router::with_meta();
+    /// router.insert_with_meta("/users/:id", "A User", "getUser")?;
+    /// router.insert_with_meta("/users", "Users", "listUsers")?;
+    ///
+    /// let mut operations = router
+    ///     .routes()
+    ///     .map(|(route, _, operation)| (route, *operation))
+    ///     .collect::<Vec<_>>();
+    /// operations.sort();
+    ///
+    /// assert_eq!(
+    ///     operations,
+    ///     [("/users".into(), "listUsers"), ("/users/:id".into(), "getUser")]
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn routes(&self) -> Routes<'_, T, S, M> {
+        Routes {
+            inner: self.root.routes(),
+        }
+    }
+
+    // Returns the value of the given route, if it was already inserted with the same
+    // parameter names.
+    pub(crate) fn route_mut(&mut self, route: &str) -> Option<&mut T> {
+        self.root.route_mut(route.as_bytes())
+    }
+
+    // Returns the entry of the value matching the given path, which may only be written
+    // to through &mut self.
+    pub(crate) fn find<'m, 'p>(
+        &'m self,
+        path: &'p [u8],
+    ) -> Result<(&'m Entry<T, M>, Params<'m, 'p>), MatchError> {
+        if let Some(value) = self.statics.get(path) {
+            return Ok((value, Params::new()));
+        }
+
+        self.root.at(path)
+    }
+}
+
+impl<T, M> Router<T, Path, M> {
+    /// Insert all routes from another router under the given prefix.
+    ///
+    /// Routes are inserted with their original parameter names, as if they had been
+    /// inserted with the prefix directly. A trailing slash in the prefix is ignored, so
+    /// nesting a route `/` under `/billing` results in `/billing/`.
+    ///
+    /// If any route can't be inserted, for example due to a conflict, the router is left
+    /// unchanged and an error is returned for each such route, named as it would have been
+    /// inserted. Checking this requires copying the structure of the router, so nesting
+    /// takes time proportional to the size of both routers.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use matchit::Router;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut billing = Router::new();
+    /// billing.insert("/invoices/:id", "Invoice")?;
+    ///
+    /// let mut router = Router::new();
+    /// router.insert("/home", "Welcome!")?;
+    /// router.nest("/billing", billing)?;
+    ///
+    /// let matched = router.at("/billing/invoices/1")?;
+    /// assert_eq!(*matched.value, "Invoice");
+    /// assert_eq!(matched.params.get("id"), Some("1"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn nest(&mut self, prefix: &str, router: Router<T, Path, M>) -> Result<(), MergeError> {
+        let prefix = prefix.strip_suffix('/').unwrap_or(prefix);
+
+        let routes = router
+            .root
+            .into_routes()
+            .into_iter()
+            .map(|(route, value, meta)| ([prefix, &route].concat(), value, meta))
+            .collect::<Vec<_>>();
+
+        // check that every route can be inserted into a copy of the tree without values
+        // before touching the router, so that it is left unchanged if any of them can't
+        let mut scratch = self.root.skeleton();
+
+        let mut errors = Vec::new();
+        for (route, _, _) in &routes {
+            let result = match self.validate {
+                true => Router::validate(route),
+                false => Ok(()),
+            };
+
+            if let Err(err) = result.and_then(|_| scratch.insert(route.clone(), ())) {
+                errors.push((route.clone(), err));
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(MergeError(errors));
+        }
+
+        // the routes are inserted in the same order as into the copy, so this only fails if
+        // the copy was wrong
+        for (route, value, meta) in routes {
+            if let Err(err) = self.insert_with_meta(route.clone(), value, meta) {
+                errors.push((route, err));
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(MergeError(errors))
+        }
+    }
+
+    /// Insert all routes from another router.
+    ///
+    /// This is equivalent to [nesting](Router::nest) the router without a prefix.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use matchit::{InsertError, Router};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut a = Router::new();
+    /// a.insert("/users/:id", "A User")?;
+    ///
+    /// let mut b = Router::new();
+    /// b.insert("/posts/:id", "A Post")?;
+    /// b.insert("/users/:name", "Conflict")?;
+    ///
+    /// let err = a.merge(b).unwrap_err();
+    /// assert_eq!(
+    ///     err.errors(),
+    ///     [(
+    ///         "/users/:name".to_owned(),
+    ///         InsertError::Conflict { with: "/users/:id".into() }
+    ///     )]
+    /// );
+    ///
+    /// // the router is left unchanged
+    /// assert!(a.at("/posts/1").is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn merge(&mut self, other: Router<T, Path, M>) -> Result<(), MergeError> {
+        self.nest("", other)
+    }
+
+    /// Transforms every value in the router, keeping the registered routes and their
+    /// metadata intact.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use matchit::Router;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut router = Router::new();
+    /// router.insert("/home", "Welcome!")?;
+    ///
+    /// let router = router.map(|value| value.len());
+    /// assert_eq!(*router.at("/home")?.value, 8);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn map<U>(self, mut f: impl FnMut(T) -> U) -> Router<U, Path, M> {
+        match self.try_map(|value| Ok::<_, core::convert::Infallible>(f(value))) {
+            Ok(router) => router,
+            Err(never) => match never {},
+        }
+    }
+
+    /// Tries to transform every value in the router, keeping the registered routes intact.
+    ///
+    /// Values are transformed in priority order, and the first error is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use matchit::Router;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut router = Router::new();
+    /// router.insert("/home", "1")?;
+    /// router.insert("/about", "2")?;
+    ///
+    /// let parsed = router.clone().try_map(|value| value.parse::<u32>())?;
+    /// assert_eq!(*parsed.at("/about")?.value, 2);
+    ///
+    /// router.insert("/blog", "three")?;
+    /// assert!(router.try_map(|value| value.parse::<u32>()).is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn try_map<U, E>(
+        self,
+        mut f: impl FnMut(T) -> Result<U, E>,
+    ) -> Result<Router<U, Path, M>, E> {
+        let root = self.root.try_map(&mut f)?;
+        Ok(Router {
+            statics: StaticIndex::build(&root),
+            root,
+            validate: self.validate,
+        })
+    }
+
+    /// Removes all routes whose values do not satisfy the predicate.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use matchit::Router;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut router = Router::new();
+    /// router.insert("/users/:id", 1)?;
+    /// router.insert("/users/:id/posts", 2)?;
+    ///
+    /// router.retain(|&value| value != 2);
+    /// assert!(router.at("/users/1").is_ok());
+    /// assert!(router.at("/users/1/posts").is_err());
+    ///
+    /// // the route can be registered again
+    /// router.insert("/users/:user_id/posts", 3)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn retain(&mut self, mut f: impl FnMut(&T) -> bool) {
+        self.root.retain(&mut f);
+        self.statics = StaticIndex::build(&self.root);
+    }
+
+    /// Returns an iterator over mutable references to all values in the router,
+    /// in priority order.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use matchit::Router;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut router = Router::new();
+    /// router.insert("/home", 1)?;
+    /// router.insert("/users/:id", 2)?;
+    ///
+    /// for value in router.values_mut() {
+    ///     *value *= 10;
+    /// }
+    ///
+    /// assert_eq!(*router.at("/users/1")?.value, 20);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn values_mut(&mut self) -> ValuesMut<'_, T, M> {
+        ValuesMut {
+            inner: tree::ValuesMut::new(&mut self.root),
+        }
+    }
+}
+
+impl<T> Router<T> {
+    /// Compacts the router into a read-only representation optimized for lookups.
+    ///
+    /// See [`FrozenRouter`] for details.
+    pub fn freeze(self) -> FrozenRouter<T> {
+        FrozenRouter::new(self.root)
+    }
+}
+
+impl<T, M> Router<T, Path, M> {
+    /// Tries to find a value in the router matching the path of the given URI, ignoring its
+    /// query string and fragment.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use matchit::Router;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut router = Router::new();
+    /// router.insert("/users/:id", "A User")?;
+    ///
+    /// let matched = router.at_uri("/users/978?fields=name#top")?;
+    /// assert_eq!(matched.params.get("id"), Some("978"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn at_uri<'m, 'p>(&'m self, uri: &'p str) -> Result<Match<'m, 'p, &'m T>, MatchError> {
+        let (path, _) = query::split_uri(uri);
+        self.at(path)
+    }
+
+    /// Tries to find a value in the router matching the given path, using the buffers in
+    /// `scratch` instead of allocating.
+    ///
+    /// Unlike [`Router::at`], this never allocates once the buffers have grown to fit the
+    /// number of parameters and backtracking needed for a path. The returned parameters
+    /// borrow the scratch buffers until the match is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use matchit::{MatchScratch, Router};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut router = Router::new();
+    /// router.insert("/:a/:b/:c/:d", true)?;
+    ///
+    /// let mut scratch = MatchScratch::new();
+    /// let matched = router.at_with(&mut scratch, "/1/2/3/4")?;
+    /// assert_eq!(matched.params.get("d"), Some("4"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn at_with<'m, 'p: 's, 's>(
+        &'m self,
+        scratch: &'s mut MatchScratch<'m, &'m Node<T, Path, M>>,
+        path: &'p str,
+    ) -> Result<Match<'s, 's, &'m T>, MatchError> {
+        if let Some(value) = self.statics.get(path.as_bytes()) {
+            return Ok(Match {
+                // SAFETY: We only expose &mut T through &mut self
+                value: unsafe { &*value.value.get() },
+                params: Params::new(),
+            });
+        }
+
+        match tree::at_with(&self.root, scratch, path.as_bytes()) {
             Ok((value, params)) => Ok(Match {
-                // SAFETY: We have &mut self
-                value: unsafe { &mut *value.get() },
+                // SAFETY: We only expose &mut T through &mut self
+                value: unsafe { &*value.value.get() },
                 params,
             }),
             Err(e) => Err(e),
         }
     }
 
+    /// Tries to find a value in the router matching the given path, falling back to the
+    /// value of the deepest route that matched part of the path.
+    ///
+    /// The fallback is only used if no route matches at all ([`MatchError::NotFound`]),
+    /// and must match a prefix of the path ending at a segment boundary. Returns the match
+    /// along with the rest of the path that was not consumed, which is empty for a full match.
+    ///
+    /// This is useful for scoped 404 handlers. Unlike [`Router::at_prefix`], a trailing slash
+    /// mismatch is returned as an error instead of falling back to a shorter route.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use matchit::Router;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut router = Router::new();
+    /// router.insert("/api/v2", "API v2 Not Found")?;
+    /// router.insert("/api/v2/users", "Users")?;
+    ///
+    /// let (matched, rest) = router.at_or_ancestor("/api/v2/users")?;
+    /// assert_eq!((*matched.value, rest), ("Users", ""));
+    ///
+    /// let (matched, rest) = router.at_or_ancestor("/api/v2/unknown")?;
+    /// assert_eq!((*matched.value, rest), ("API v2 Not Found", "/unknown"));
+    ///
+    /// assert!(router.at_or_ancestor("/api/v1/users").is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn at_or_ancestor<'m, 'p>(
+        &'m self,
+        path: &'p str,
+    ) -> Result<(Match<'m, 'p, &'m T>, &'p str), MatchError> {
+        match self.root.at_or_ancestor(path.as_bytes()) {
+            Ok((value, params, consumed)) => Ok((
+                Match {
+                    // SAFETY: We only expose &mut T through &mut self
+                    value: unsafe { &*value.value.get() },
+                    params,
+                },
+                &path[consumed..],
+            )),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Tries to find the longest route in the router matching a prefix of the given path,
+    /// returning the match along with the rest of the path.
+    ///
+    /// Prefixes are aligned to path segments, meaning that they either end right before or
+    /// after a `/`, or span the whole path. This allows mounting values at a prefix without
+    /// registering a catch-all route.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use matchit::Router;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut router = Router::new();
+    /// router.insert("/admin", "Admin")?;
+    /// router.insert("/admin/users", "Users")?;
+    /// router.insert("/tenants/:id", "Tenant")?;
+    ///
+    /// let (matched, rest) = router.at_prefix("/admin/users/5")?;
+    /// assert_eq!((*matched.value, rest), ("Users", "/5"));
+    ///
+    /// let (matched, rest) = router.at_prefix("/admin/settings")?;
+    /// assert_eq!((*matched.value, rest), ("Admin", "/settings"));
+    ///
+    /// let (matched, rest) = router.at_prefix("/tenants/1/billing")?;
+    /// assert_eq!(matched.params.get("id"), Some("1"));
+    /// assert_eq!(rest, "/billing");
+    ///
+    /// // prefixes must be aligned to a segment
+    /// assert!(router.at_prefix("/administrator").is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn at_prefix<'m, 'p>(
+        &'m self,
+        path: &'p str,
+    ) -> Result<(Match<'m, 'p, &'m T>, &'p str), MatchError> {
+        match self.root.at_prefix(path.as_bytes()) {
+            Ok((value, params, consumed)) => Ok((
+                Match {
+                    // SAFETY: We only expose &mut T through &mut self
+                    value: unsafe { &*value.value.get() },
+                    params,
+                },
+                &path[consumed..],
+            )),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Returns an iterator over all values in the router matching the given path,
+    /// in priority order.
+    ///
+    /// The first match is the one that would be returned by [`Router::at`]. Routes that
+    /// are shadowed by a higher priority route, for example a static route overlapping
+    /// a parameter, are returned after it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use matchit::Router;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut router = Router::new();
+    /// router.insert("/users/new", "New User")?;
+    /// router.insert("/users/:id", "A User")?;
+    /// router.insert("/*path", "Fallback")?;
+    ///
+    /// let values = router.matches("/users/new").map(|m| *m.value).collect::<Vec<_>>();
+    /// assert_eq!(values, ["New User", "A User", "Fallback"]);
+    ///
+    /// let mut matches = router.matches("/users/978");
+    /// assert_eq!(matches.next().unwrap().params.get("id"), Some("978"));
+    /// assert_eq!(matches.next().unwrap().params.get("path"), Some("users/978"));
+    /// assert!(matches.next().is_none());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn matches<'m, 'p>(&'m self, path: &'p str) -> Matches<'m, 'p, T, M> {
+        Matches {
+            inner: self.root.matches(path.as_bytes()),
+        }
+    }
+
     #[cfg(feature = "__test_helpers")]
     pub fn check_priorities(&self) -> Result<u32, (u32, u32)> {
         self.root.check_priorities()
     }
 }
 
+impl Router<usize> {
+    /// Generates the Rust source of a [`StaticNode`](crate::StaticNode) table for the router.
+    ///
+    /// The values of the router are used as indices into the values passed to
+    /// [`StaticRouter::from_table`](crate::StaticRouter::from_table). This is meant to be
+    /// used in a build script, so that the routes are validated and laid out before the
+    /// program is compiled.
+    ///
+    /// ```rust,ignore
+    /// // build.rs
+    /// let mut router = matchit::Router::new();
+    /// router.insert("/home", 0).unwrap();
+    /// router.insert("/users/:id", 1).unwrap();
+    ///
+    /// let out = std::env::var("OUT_DIR").unwrap();
+    /// std::fs::write(format!("{}/routes.rs", out), router.to_static_table()).unwrap();
+    ///
+    /// // main.rs
+    /// static ROOT: matchit::StaticNode = include!(concat!(env!("OUT_DIR"), "/routes.rs"));
+    /// ```
+    pub fn to_static_table(&self) -> String {
+        let mut out = String::new();
+        static_router::write_table(&self.root, &mut out);
+        out
+    }
+}
+
 /// A successful match consisting of the registered value
 /// and URL parameters, returned by [`Router::at`](Router::at).
 #[derive(Debug)]
@@ -113,3 +888,62 @@ pub struct Match<'k, 'v, V> {
     /// The route parameters. See [parameters](crate#parameters) for more details.
     pub params: Params<'k, 'v>,
 }
+
+/// A successful match against a path of raw bytes, returned by [`Router::at_bytes`].
+///
+/// This is the same as [`Match`], except that the parameters don't have to be valid UTF-8.
+#[derive(Debug)]
+pub struct BytesMatch<'k, 'v, V> {
+    /// The value stored under the matched node.
+    pub value: V,
+    /// The route parameters, which are only accessible as bytes.
+    pub params: BytesParams<'k, 'v>,
+}
+
+/// An iterator over all values matching a path, returned by [`Router::matches`].
+pub struct Matches<'m, 'p, T, M = ()> {
+    inner: tree::Matches<'m, 'p, &'m Node<T, Path, M>>,
+}
+
+impl<'m, 'p, T, M> Iterator for Matches<'m, 'p, T, M> {
+    type Item = Match<'m, 'p, &'m T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(value, params)| Match {
+            // SAFETY: We only expose &mut T through &mut self
+            value: unsafe { &*value.value.get() },
+            params,
+        })
+    }
+}
+
+/// An iterator over the routes of a router along with their values and metadata,
+/// returned by [`Router::routes`].
+pub struct Routes<'m, T, S = Path, M = ()> {
+    inner: tree::Routes<'m, T, S, M>,
+}
+
+impl<'m, T, S: Syntax, M> Iterator for Routes<'m, T, S, M> {
+    type Item = (String, &'m T, &'m M);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(route, value)| {
+            // SAFETY: We only expose &mut T through &mut self
+            (route, unsafe { &*value.value.get() }, &value.meta)
+        })
+    }
+}
+
+/// An iterator over mutable references to the values of a router, returned by
+/// [`Router::values_mut`].
+pub struct ValuesMut<'m, T, M = ()> {
+    inner: tree::ValuesMut<'m, T, M>,
+}
+
+impl<'m, T, M> Iterator for ValuesMut<'m, T, M> {
+    type Item = &'m mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+}
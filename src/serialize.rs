@@ -0,0 +1,224 @@
+//! Serialization of a built router, behind the `serde` feature.
+//!
+//! The tree is persisted as is, so that loading a router doesn't have to insert every
+//! route again. Because a malformed tree would make matching misbehave, every invariant
+//! that insertion upholds is checked when deserializing.
+
+use crate::index::StaticIndex;
+use crate::tree::{self, Node, NodeType, Slot};
+use crate::Router;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use core::fmt;
+use core::marker::PhantomData;
+use core::str;
+
+use serde::de::{self, Deserialize, Deserializer, SeqAccess, Visitor};
+use serde::ser::{Serialize, SerializeSeq, SerializeStruct, Serializer};
+
+impl<T: Serialize> Serialize for Router<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Router", 2)?;
+        state.serialize_field("root", &self.root)?;
+        state.serialize_field("validate", &self.validate)?;
+        state.end()
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Router<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "Router")]
+        struct RawRouter<T> {
+            root: RawNode<T>,
+            validate: bool,
+        }
+
+        let raw = RawRouter::<T>::deserialize(deserializer)?;
+        let (root, priorities) = raw.root.into_node().map_err(de::Error::custom)?;
+        tree::validate(&root).map_err(de::Error::custom)?;
+
+        if !priorities {
+            return Err(de::Error::custom(
+                "invalid router: the priority of a node does not match its children",
+            ));
+        }
+
+        Ok(Router {
+            statics: StaticIndex::build(&root),
+            root,
+            validate: raw.validate,
+        })
+    }
+}
+
+impl<T: Serialize> Serialize for Node<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // SAFETY: we only expose &mut T through &mut self
+        let value = self
+            .value
+            .as_ref()
+            .map(|value| unsafe { &*value.value.get() });
+
+        let mut state = serializer.serialize_struct("Node", 8)?;
+        state.serialize_field("prefix", &Bytes(&self.prefix))?;
+        state.serialize_field("indices", &Bytes(&self.indices))?;
+        state.serialize_field("node_type", &self.node_type)?;
+        state.serialize_field("wild_child", &self.wild_child)?;
+        state.serialize_field("priority", &self.priority)?;
+        state.serialize_field("param_remapping", &Remapping(&self.param_remapping))?;
+        state.serialize_field("value", &value)?;
+        state.serialize_field("children", &self.children)?;
+        state.end()
+    }
+}
+
+// A node as it was deserialized, before its invariants are checked.
+#[derive(serde::Deserialize)]
+#[serde(rename = "Node")]
+struct RawNode<T> {
+    prefix: ByteBuf,
+    indices: ByteBuf,
+    node_type: NodeType,
+    wild_child: bool,
+    priority: u32,
+    param_remapping: Vec<ByteBuf>,
+    value: Option<T>,
+    children: Vec<RawNode<T>>,
+}
+
+// A node being converted, with the children that are left to convert.
+struct Pending<T> {
+    node: Node<T>,
+    priority: u32,
+    children: vec::IntoIter<RawNode<T>>,
+}
+
+impl<T> RawNode<T> {
+    // Converts the node into a `Node`, along with whether the priority of every node
+    // matches its children. The rest of the tree is checked by `tree::validate`.
+    //
+    // The tree is converted with an explicit stack, as it may be arbitrarily deep.
+    fn into_node(self) -> Result<(Node<T>, bool), &'static str> {
+        let mut stack = vec![self.pending()];
+        let mut priorities = true;
+
+        loop {
+            let top = stack.last_mut().unwrap();
+
+            if let Some(child) = top.children.next() {
+                stack.push(child.pending());
+                continue;
+            }
+
+            let Pending {
+                mut node, priority, ..
+            } = stack.pop().unwrap();
+
+            node.priority = node
+                .children
+                .iter()
+                .try_fold(u32::from(node.value.is_some()), |sum, child| {
+                    sum.checked_add(child.priority)
+                })
+                .ok_or("invalid router: too many routes")?;
+
+            priorities &= node.priority == priority;
+
+            match stack.last_mut() {
+                Some(parent) => parent.node.children.push(node),
+                None => return Ok((node, priorities)),
+            }
+        }
+    }
+
+    // Converts the node without its children.
+    fn pending(self) -> Pending<T> {
+        Pending {
+            node: Node {
+                priority: 0,
+                wild_child: self.wild_child,
+                indices: self.indices.0,
+                value: self.value.map(|value| Slot::new(value, ())),
+                param_remapping: self
+                    .param_remapping
+                    .into_iter()
+                    .map(|param| param.0)
+                    .collect(),
+                node_type: self.node_type,
+                prefix: self.prefix.0,
+                children: Vec::with_capacity(self.children.len()),
+                syntax: PhantomData,
+            },
+            priority: self.priority,
+            children: self.children.into_iter(),
+        }
+    }
+}
+
+// Serializes a byte string compactly in formats that support it.
+struct Bytes<'a>(&'a [u8]);
+
+impl Serialize for Bytes<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(self.0)
+    }
+}
+
+struct Remapping<'a>(&'a [Vec<u8>]);
+
+impl Serialize for Remapping<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.0.len()))?;
+        for param in self.0 {
+            seq.serialize_element(&Bytes(param))?;
+        }
+        seq.end()
+    }
+}
+
+// Deserializes a byte string from either bytes or a sequence, as formats without a
+// byte string type serialize them as sequences.
+struct ByteBuf(Vec<u8>);
+
+impl<'de> Deserialize<'de> for ByteBuf {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ByteBufVisitor;
+
+        impl<'de> Visitor<'de> for ByteBufVisitor {
+            type Value = ByteBuf;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a byte string")
+            }
+
+            fn visit_bytes<E: de::Error>(self, bytes: &[u8]) -> Result<ByteBuf, E> {
+                Ok(ByteBuf(bytes.into()))
+            }
+
+            fn visit_byte_buf<E: de::Error>(self, bytes: Vec<u8>) -> Result<ByteBuf, E> {
+                Ok(ByteBuf(bytes))
+            }
+
+            fn visit_str<E: de::Error>(self, s: &str) -> Result<ByteBuf, E> {
+                Ok(ByteBuf(s.as_bytes().into()))
+            }
+
+            fn visit_string<E: de::Error>(self, s: String) -> Result<ByteBuf, E> {
+                Ok(ByteBuf(s.into_bytes()))
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<ByteBuf, A::Error> {
+                let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+                while let Some(byte) = seq.next_element()? {
+                    bytes.push(byte);
+                }
+                Ok(ByteBuf(bytes))
+            }
+        }
+
+        deserializer.deserialize_byte_buf(ByteBufVisitor)
+    }
+}
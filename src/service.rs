@@ -0,0 +1,256 @@
+//! Integration with [`tower`](https://docs.rs/tower), behind the `tower` feature.
+
+use crate::{MatchError, OwnedParams, Router};
+use alloc::sync::Arc;
+
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{ready, Context, Poll};
+
+use http::Request;
+use pin_project_lite::pin_project;
+use tower_layer::Layer;
+use tower_service::Service;
+
+/// A [`Service`] that routes requests to the service matching their path.
+///
+/// The parameters of the matched route are inserted into the extensions of the request
+/// as [`OwnedParams`]. Requests that don't match any route are passed to the fallback
+/// service, along with the [`MatchError`] in their extensions. Paths that only match a
+/// route with or without a trailing slash can be passed to a separate fallback, for
+/// example to redirect them, with [`RouterService::trailing_slash_fallback`].
+///
+/// ```rust
+/// use http::{Request, Response};
+/// use matchit::{OwnedParams, Router, RouterService};
+/// use tower::{service_fn, ServiceExt};
+///
+/// type Handler = fn(Request<()>) -> std::future::Ready<Result<Response<String>, ()>>;
+///
+/// fn user(req: Request<()>) -> std::future::Ready<Result<Response<String>, ()>> {
+///     let params = req.extensions().get::<OwnedParams>().unwrap();
+///     std::future::ready(Ok(Response::new(format!("user {}", params.get("id").unwrap()))))
+/// }
+///
+/// fn not_found(_: Request<()>) -> std::future::Ready<Result<Response<String>, ()>> {
+///     std::future::ready(Ok(Response::new("not found".to_owned())))
+/// }
+///
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut router = Router::new();
+/// router.insert("/users/:id", service_fn(user as Handler))?;
+///
+/// let service = RouterService::new(router, service_fn(not_found as Handler));
+///
+/// let request = Request::get("/users/978").body(()).unwrap();
+/// let response = service.clone().oneshot(request).await.unwrap();
+/// assert_eq!(response.body(), "user 978");
+///
+/// let request = Request::get("/posts").body(()).unwrap();
+/// let response = service.oneshot(request).await.unwrap();
+/// assert_eq!(response.body(), "not found");
+/// # Ok(())
+/// # }
+/// ```
+pub struct RouterService<S, F, T = F> {
+    router: Arc<Router<S>>,
+    fallback: F,
+    trailing_slash: Option<T>,
+}
+
+impl<S, F> RouterService<S, F> {
+    /// Creates a service that routes requests with the given router, passing requests that
+    /// don't match any route to `fallback`.
+    pub fn new(router: Router<S>, fallback: F) -> Self {
+        Self {
+            router: Arc::new(router),
+            fallback,
+            trailing_slash: None,
+        }
+    }
+}
+
+impl<S, F, T> RouterService<S, F, T> {
+    /// Passes requests whose path would match a route with or without a trailing slash to
+    /// the given service, instead of the fallback service.
+    ///
+    /// The service can be of a different type than the fallback service.
+    pub fn trailing_slash_fallback<U>(self, service: U) -> RouterService<S, F, U> {
+        RouterService {
+            router: self.router,
+            fallback: self.fallback,
+            trailing_slash: Some(service),
+        }
+    }
+}
+
+impl<S, F: Clone, T: Clone> Clone for RouterService<S, F, T> {
+    fn clone(&self) -> Self {
+        Self {
+            router: self.router.clone(),
+            fallback: self.fallback.clone(),
+            trailing_slash: self.trailing_slash.clone(),
+        }
+    }
+}
+
+impl<S, F, T, B> Service<Request<B>> for RouterService<S, F, T>
+where
+    S: Service<Request<B>> + Clone,
+    F: Service<Request<B>, Response = S::Response, Error = S::Error> + Clone,
+    T: Service<Request<B>, Response = S::Response, Error = S::Error> + Clone,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = RouteFuture<S, F, T, Request<B>>;
+
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        // every request is handled by a fresh clone of a service, which is driven to
+        // readiness by the returned future
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, mut req: Request<B>) -> Self::Future {
+        let state = match self.router.at(req.uri().path()) {
+            Ok(matched) => {
+                let service = matched.value.clone();
+                let params = OwnedParams::from(matched.params);
+                req.extensions_mut().insert(params);
+
+                State::Route {
+                    service,
+                    request: Some(req),
+                }
+            }
+            Err(err) => {
+                let trailing_slash = match err {
+                    MatchError::NotFound => None,
+                    _ => self.trailing_slash.clone(),
+                };
+
+                req.extensions_mut().insert(err);
+
+                match trailing_slash {
+                    Some(service) => State::TrailingSlash {
+                        service,
+                        request: Some(req),
+                    },
+                    None => State::Fallback {
+                        service: self.fallback.clone(),
+                        request: Some(req),
+                    },
+                }
+            }
+        };
+
+        RouteFuture { state }
+    }
+}
+
+/// A [`Layer`] that routes requests with a [`Router`], passing requests that don't
+/// match any route to the inner service.
+///
+/// See [`RouterService`] for details.
+pub struct RouterLayer<S> {
+    router: Arc<Router<S>>,
+}
+
+impl<S> RouterLayer<S> {
+    /// Creates a layer that routes requests with the given router.
+    pub fn new(router: Router<S>) -> Self {
+        Self {
+            router: Arc::new(router),
+        }
+    }
+}
+
+impl<S> Clone for RouterLayer<S> {
+    fn clone(&self) -> Self {
+        Self {
+            router: self.router.clone(),
+        }
+    }
+}
+
+impl<S, F> Layer<F> for RouterLayer<S> {
+    type Service = RouterService<S, F>;
+
+    fn layer(&self, fallback: F) -> Self::Service {
+        RouterService {
+            router: self.router.clone(),
+            fallback,
+            trailing_slash: None,
+        }
+    }
+}
+
+pin_project! {
+    /// The response future of a [`RouterService`].
+    pub struct RouteFuture<S, F, T, R>
+    where
+        S: Service<R>,
+        F: Service<R>,
+        T: Service<R>,
+    {
+        #[pin]
+        state: State<S, F, T, R>,
+    }
+}
+
+pin_project! {
+    #[project = StateProj]
+    enum State<S, F, T, R>
+    where
+        S: Service<R>,
+        F: Service<R>,
+        T: Service<R>,
+    {
+        // waiting for the service to be ready
+        Route { service: S, request: Option<R> },
+        Fallback { service: F, request: Option<R> },
+        TrailingSlash { service: T, request: Option<R> },
+        // waiting for the response
+        RouteCalled { #[pin] future: S::Future },
+        FallbackCalled { #[pin] future: F::Future },
+        TrailingSlashCalled { #[pin] future: T::Future },
+    }
+}
+
+impl<S, F, T, R> Future for RouteFuture<S, F, T, R>
+where
+    S: Service<R>,
+    F: Service<R, Response = S::Response, Error = S::Error>,
+    T: Service<R, Response = S::Response, Error = S::Error>,
+{
+    type Output = Result<S::Response, S::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.project().state;
+
+        loop {
+            let next = match state.as_mut().project() {
+                StateProj::Route { service, request } => {
+                    ready!(service.poll_ready(cx))?;
+                    let future = service.call(request.take().expect("polled after completion"));
+                    State::RouteCalled { future }
+                }
+                StateProj::Fallback { service, request } => {
+                    ready!(service.poll_ready(cx))?;
+                    let future = service.call(request.take().expect("polled after completion"));
+                    State::FallbackCalled { future }
+                }
+                StateProj::TrailingSlash { service, request } => {
+                    ready!(service.poll_ready(cx))?;
+                    let future = service.call(request.take().expect("polled after completion"));
+                    State::TrailingSlashCalled { future }
+                }
+                StateProj::RouteCalled { future } => return future.poll(cx),
+                StateProj::FallbackCalled { future } => return future.poll(cx),
+                StateProj::TrailingSlashCalled { future } => return future.poll(cx),
+            };
+
+            state.set(next);
+        }
+    }
+}
@@ -0,0 +1,363 @@
+use crate::tree::{self, Lookup, Node, NodeType};
+use crate::{Match, MatchError};
+use alloc::string::String;
+
+use core::ascii;
+use core::fmt::Write;
+
+/// A read-only router whose tree is laid out in static memory.
+///
+/// Static routers are created with the `router!` macro from the `matchit-macros` crate,
+/// or from a table generated by [`Router::to_static_table`](crate::Router::to_static_table),
+/// for example in a build script. Either way, the routes are validated and checked for
+/// conflicts before the program is compiled, and matching has the exact same semantics
+/// as [`Router::at`](crate::Router::at).
+///
+/// ```rust
+/// use matchit_macros::router;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let router = router! {
+///     "/home" => "Welcome!",
+///     "/users/:id" => "A User",
+/// };
+///
+/// let matched = router.at("/users/978")?;
+/// assert_eq!(matched.params.get("id"), Some("978"));
+/// assert_eq!(*matched.value, "A User");
+/// # Ok(())
+/// # }
+/// ```
+pub struct StaticRouter<T, const N: usize> {
+    root: &'static StaticNode,
+    values: [T; N],
+}
+
+impl<T, const N: usize> StaticRouter<T, N> {
+    /// Creates a router from a table generated by [`Router::to_static_table`](crate::Router::to_static_table).
+    ///
+    /// The values of the nodes in the table are indices into `values`. Matching a path whose
+    /// value is out of bounds panics.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the table could match parameters that are not valid UTF-8, which is never
+    /// the case for generated tables. For a table in a `static`, this is a compile error.
+    ///
+    /// ```rust,ignore
+    /// use matchit::{StaticNode, StaticRouter};
+    ///
+    /// fn home() {}
+    /// fn user() {}
+    ///
+    /// static ROOT: StaticNode = include!(concat!(env!("OUT_DIR"), "/routes.rs"));
+    /// static ROUTER: StaticRouter<fn(), 2> = StaticRouter::from_table(&ROOT, [home, user]);
+    /// ```
+    pub const fn from_table(root: &'static StaticNode, values: [T; N]) -> Self {
+        assert!(check_utf8(root, 0), "invalid table");
+        Self { root, values }
+    }
+
+    /// Tries to find a value in the router matching the given path.
+    pub fn at<'m, 'p>(&'m self, path: &'p str) -> Result<Match<'m, 'p, &'m T>, MatchError> {
+        match tree::at(self.root, path.as_bytes()) {
+            Ok((&value, params)) => Ok(Match {
+                value: &self.values[value],
+                params,
+            }),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Tries to find a value in the router matching the given path,
+    /// returning a mutable reference.
+    pub fn at_mut<'m, 'p>(
+        &'m mut self,
+        path: &'p str,
+    ) -> Result<Match<'m, 'p, &'m mut T>, MatchError> {
+        match tree::at(self.root, path.as_bytes()) {
+            Ok((&value, params)) => Ok(Match {
+                value: &mut self.values[value],
+                params,
+            }),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+// Checks that the parameters matched by the given node are valid UTF-8, see `Params`. This is
+// the case as long as parameter names are valid UTF-8, and parameters start on a character
+// boundary of the route.
+//
+// `pending` is the number of continuation bytes that the prefixes of the ancestors of the
+// node are missing for their last character.
+const fn check_utf8(node: &StaticNode, mut pending: u8) -> bool {
+    match node.node_type {
+        NodeType::Param | NodeType::CatchAll => {
+            let name = match node.prefix.split_first() {
+                Some((_, name)) => name,
+                None => return false,
+            };
+
+            if pending != 0 || !is_utf8(name) {
+                return false;
+            }
+        }
+        NodeType::Root | NodeType::Static => {
+            let mut i = 0;
+            while i < node.prefix.len() {
+                let b = node.prefix[i];
+                pending = match (pending, b) {
+                    (0, 0x00..=0x7f) => 0,
+                    (0, 0xc0..=0xdf) => 1,
+                    (0, 0xe0..=0xef) => 2,
+                    (0, 0xf0..=0xf7) => 3,
+                    (1.., 0x80..=0xbf) => pending - 1,
+                    _ => return false,
+                };
+                i += 1;
+            }
+        }
+    }
+
+    let mut i = 0;
+    while i < node.params.len() {
+        if !is_utf8(node.params[i]) {
+            return false;
+        }
+        i += 1;
+    }
+
+    let mut i = 0;
+    while i < node.children.len() {
+        if !check_utf8(&node.children[i], pending) {
+            return false;
+        }
+        i += 1;
+    }
+
+    true
+}
+
+// Returns `true` if the bytes are valid UTF-8, like `str::from_utf8`, which is only a const fn
+// since Rust 1.63.
+const fn is_utf8(bytes: &[u8]) -> bool {
+    let mut i = 0;
+    while i < bytes.len() {
+        // the range of the second byte of the character, and the number of bytes after the first
+        let (min, max, len) = match bytes[i] {
+            0x00..=0x7f => {
+                i += 1;
+                continue;
+            }
+            0xc2..=0xdf => (0x80, 0xbf, 1),
+            0xe0 => (0xa0, 0xbf, 2),
+            0xe1..=0xec | 0xee..=0xef => (0x80, 0xbf, 2),
+            0xed => (0x80, 0x9f, 2),
+            0xf0 => (0x90, 0xbf, 3),
+            0xf1..=0xf3 => (0x80, 0xbf, 3),
+            0xf4 => (0x80, 0x8f, 3),
+            _ => return false,
+        };
+
+        if i + len >= bytes.len() || bytes[i + 1] < min || bytes[i + 1] > max {
+            return false;
+        }
+
+        let mut j = 2;
+        while j <= len {
+            if bytes[i + j] & 0xc0 != 0x80 {
+                return false;
+            }
+            j += 1;
+        }
+
+        i += len + 1;
+    }
+
+    true
+}
+
+/// A node in the table of a [`StaticRouter`].
+///
+/// Tables are generated by [`Router::to_static_table`](crate::Router::to_static_table) or
+/// the `router!` macro, and their layout is an implementation detail.
+#[derive(Debug)]
+pub struct StaticNode {
+    prefix: &'static [u8],
+    indices: &'static [u8],
+    node_type: NodeType,
+    wild_child: bool,
+    // an index into the values of the router
+    value: Option<usize>,
+    // the parameter names of the route ending at this node, without the leading `:`
+    params: &'static [&'static [u8]],
+    children: &'static [StaticNode],
+}
+
+/// The first version of the format of generated tables.
+///
+/// Generated tables only refer to this module, so a table generated by an incompatible
+/// version of matchit fails to compile instead of being misread. Any change to the format
+/// needs a new version.
+pub mod v1 {
+    use super::StaticNode;
+    use crate::tree::NodeType;
+
+    /// Creates a node of a table.
+    pub const fn node(
+        prefix: &'static [u8],
+        indices: &'static [u8],
+        node_type: u8,
+        wild_child: bool,
+        value: Option<usize>,
+        params: &'static [&'static [u8]],
+        children: &'static [StaticNode],
+    ) -> StaticNode {
+        let node_type = match node_type {
+            0 => NodeType::Root,
+            1 => NodeType::Param,
+            2 => NodeType::CatchAll,
+            3 => NodeType::Static,
+            _ => panic!("invalid node type"),
+        };
+
+        StaticNode {
+            prefix,
+            indices,
+            node_type,
+            wild_child,
+            value,
+            params,
+            children,
+        }
+    }
+
+    // Encodes a node type for `node`.
+    pub(crate) fn node_type(node_type: NodeType) -> u8 {
+        match node_type {
+            NodeType::Root => 0,
+            NodeType::Param => 1,
+            NodeType::CatchAll => 2,
+            NodeType::Static => 3,
+        }
+    }
+}
+
+impl Lookup<'static> for &'static StaticNode {
+    type Value = usize;
+
+    fn prefix(self) -> &'static [u8] {
+        self.prefix
+    }
+
+    fn indices(self) -> &'static [u8] {
+        self.indices
+    }
+
+    fn node_type(self) -> NodeType {
+        self.node_type
+    }
+
+    fn wild_child(self) -> bool {
+        self.wild_child
+    }
+
+    fn value(self) -> Option<&'static usize> {
+        self.value.as_ref()
+    }
+
+    fn children_len(self) -> usize {
+        self.children.len()
+    }
+
+    fn child(self, i: usize) -> Self {
+        &self.children[i]
+    }
+
+    fn param_name(self, i: usize) -> &'static [u8] {
+        self.params[i]
+    }
+}
+
+// Writes the source of a `StaticNode` with the same structure as the given node, in the
+// format of `v1::node`.
+pub(crate) fn write_table(node: &Node<usize>, out: &mut String) {
+    out.push_str("::matchit::__private::v1::node(");
+    write_bytes(node.prefix(), out);
+    out.push_str(", ");
+    write_bytes(node.indices(), out);
+
+    let _ = write!(
+        out,
+        ", {}, {}, ",
+        v1::node_type(node.node_type()),
+        node.wild_child()
+    );
+
+    match node.value() {
+        // SAFETY: we only expose &mut T through &mut self
+        Some(value) => {
+            let _ = write!(out, "::core::option::Option::Some({})", unsafe {
+                *value.value.get()
+            });
+        }
+        None => out.push_str("::core::option::Option::None"),
+    }
+
+    out.push_str(", &[");
+    for i in 0..node.param_remapping.len() {
+        write_bytes(node.param_name(i), out);
+        out.push_str(", ");
+    }
+
+    out.push_str("], &[");
+    for child in &node.children {
+        write_table(child, out);
+        out.push_str(", ");
+    }
+
+    out.push_str("])");
+}
+
+// Writes a byte string literal.
+fn write_bytes(bytes: &[u8], out: &mut String) {
+    out.push_str("b\"");
+    out.extend(
+        bytes
+            .iter()
+            .flat_map(|&b| ascii::escape_default(b))
+            .map(char::from),
+    );
+    out.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_utf8_matches_std() {
+        // the bytes around the boundaries of the ranges of continuation bytes
+        let edges = [0x00, 0x7f, 0x80, 0x8f, 0x90, 0x9f, 0xa0, 0xbf, 0xc0, 0xff];
+
+        for a in 0..=0xff_u8 {
+            for b in 0..=0xff_u8 {
+                let case = [a, b];
+                assert_eq!(is_utf8(&case), core::str::from_utf8(&case).is_ok());
+            }
+
+            for &b in &edges {
+                for &c in &edges {
+                    let case = [a, b, c];
+                    assert_eq!(is_utf8(&case), core::str::from_utf8(&case).is_ok());
+
+                    for &d in &edges {
+                        let case = [a, b, c, d];
+                        assert_eq!(is_utf8(&case), core::str::from_utf8(&case).is_ok());
+                    }
+                }
+            }
+        }
+    }
+}
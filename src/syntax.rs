@@ -0,0 +1,88 @@
+use crate::{tree, InsertError};
+
+/// The syntax of the routes of a [`Router`](crate::Router).
+///
+/// Routers are not limited to URL paths: any hierarchical key can be matched, as long as
+/// its segments are separated by a single byte. The syntax describes that separator, along
+/// with the bytes that start named and catch-all parameters. Routes of any syntax share
+/// the same matching semantics, including [routing priority](crate#routing-priority) and
+/// trailing separator recommendations.
+///
+/// The bytes of the syntax must be ASCII, so that parameters are never split in the middle
+/// of a character. Inserting into a router with any other syntax fails to compile.
+///
+/// ```rust
+/// use matchit::{Router, Syntax};
+///
+/// // metric names like `http.requests.total`
+/// struct Metric;
+///
+/// impl Syntax for Metric {
+///     const SEPARATOR: u8 = b'.';
+///     const PARAM: u8 = b'$';
+///     const CATCH_ALL: u8 = b'#';
+/// }
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut router = Router::<_, Metric>::with_syntax();
+/// router.insert("http.$service.requests", "Requests")?;
+/// router.insert("db.#rest", "Database")?;
+///
+/// let matched = router.at("http.api.requests")?;
+/// assert_eq!(*matched.value, "Requests");
+/// assert_eq!(matched.params.get("service"), Some("api"));
+///
+/// let matched = router.at("db.queries.slow")?;
+/// assert_eq!(matched.params.get("rest"), Some("queries.slow"));
+/// # Ok(())
+/// # }
+/// ```
+///
+/// ```rust,compile_fail
+/// use matchit::{Router, Syntax};
+///
+/// struct Latin1;
+///
+/// impl Syntax for Latin1 {
+///     const SEPARATOR: u8 = 0xb7;
+///     const PARAM: u8 = b':';
+///     const CATCH_ALL: u8 = b'*';
+/// }
+///
+/// let mut router = Router::<_, Latin1>::with_syntax();
+/// router.insert("a\u{b7}:b", true).unwrap();
+/// ```
+pub trait Syntax {
+    /// The byte separating the segments of a route, which named parameters can't span.
+    const SEPARATOR: u8;
+    /// The byte starting a named parameter, like `:` in `/:id`.
+    const PARAM: u8;
+    /// The byte starting a catch-all parameter, like `*` in `/*path`.
+    const CATCH_ALL: u8;
+
+    /// Checks that a route is well-formed, which is done before inserting it into a router
+    /// unless validation is [disabled](crate::Router::set_validation).
+    ///
+    /// By default, this only checks that parameters are named, and that catch-all
+    /// parameters are at the end of the route.
+    fn validate(route: &[u8]) -> Result<(), InsertError> {
+        tree::validate_params::<Self>(route)
+    }
+}
+
+/// The syntax of URL paths, like `/users/:id` or `/files/*path`, used by default.
+///
+/// Routes must start with `/`, and may only contain characters allowed in a URL path.
+/// See [`Router::validate`](crate::Router::validate) for details.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Path;
+
+impl Syntax for Path {
+    const SEPARATOR: u8 = b'/';
+    const PARAM: u8 = b':';
+    const CATCH_ALL: u8 = b'*';
+
+    fn validate(route: &[u8]) -> Result<(), InsertError> {
+        tree::validate_route(route)
+    }
+}
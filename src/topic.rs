@@ -0,0 +1,212 @@
+use crate::syntax::Syntax;
+use crate::tree::Node;
+use crate::InsertError;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+// Every level of a topic is stored with this byte in front of it, so that empty levels
+// can still be matched by parameters. It can't appear in MQTT topics.
+const LEVEL: u8 = b'\0';
+
+/// A router for MQTT topic filters, returning every filter that matches a topic.
+///
+/// Topics are made of levels separated by `/`. In a filter, a level can be a `+`, which
+/// matches any single level, or a `#` at the end, which matches any number of levels,
+/// including none. For example, `sport/#` matches `sport`, `sport/tennis` and
+/// `sport/tennis/player1`.
+///
+/// As in MQTT, filters starting with a wildcard don't match topics starting with `$`,
+/// which are reserved for the broker.
+///
+/// ```rust
+/// use matchit::TopicRouter;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut router = TopicRouter::new();
+/// router.insert("sport/tennis/+", "Scores")?;
+/// router.insert("sport/#", "Sport")?;
+/// router.insert("#", "Everything")?;
+///
+/// assert_eq!(router.matches("sport/tennis/player1"), [&"Scores", &"Sport", &"Everything"]);
+/// assert_eq!(router.matches("sport"), [&"Sport", &"Everything"]);
+/// assert_eq!(router.matches("$SYS/uptime"), [] as [&&str; 0]);
+/// # Ok(())
+/// # }
+/// ```
+pub struct TopicRouter<T> {
+    // filters without a multi-level wildcard, as indices into `values`
+    exact: Node<usize, Topic>,
+    // the parent levels of filters ending in a multi-level wildcard
+    parents: Node<usize, Topic>,
+    // filters that are only a multi-level wildcard
+    all: Option<usize>,
+    // the values of the filters, and whether the filter starts with a wildcard
+    values: Vec<(T, bool)>,
+}
+
+// Encoded topic filters, where every level starts with `LEVEL` or is a `+` parameter.
+struct Topic;
+
+impl Syntax for Topic {
+    const SEPARATOR: u8 = b'/';
+    const PARAM: u8 = b'+';
+    const CATCH_ALL: u8 = b'#';
+}
+
+impl<T> Default for TopicRouter<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> TopicRouter<T> {
+    /// Construct a new router.
+    pub const fn new() -> Self {
+        Self {
+            exact: Node::new(),
+            parents: Node::new(),
+            all: None,
+            values: Vec::new(),
+        }
+    }
+
+    /// Insert a topic filter into the router.
+    ///
+    /// Inserting the same filter twice fails with [`InsertError::Conflict`].
+    ///
+    /// ```rust
+    /// # use matchit::{InsertError, TopicRouter};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut router = TopicRouter::new();
+    /// router.insert("sensors/+/temperature", 1)?;
+    /// router.insert("sensors/#", 2)?;
+    ///
+    /// assert_eq!(router.insert("sensors/#/x", 3), Err(InsertError::InvalidCatchAll));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn insert(&mut self, filter: impl Into<String>, value: T) -> Result<(), InsertError> {
+        let filter = filter.into();
+        let index = self.values.len();
+
+        if filter.is_empty() {
+            return Err(InsertError::InvalidRoute);
+        }
+
+        let (levels, multi_level) = match filter.strip_suffix('#') {
+            Some("") => (None, true),
+            Some(parent) => match parent.strip_suffix('/') {
+                Some(parent) => (Some(parent), true),
+                None => return Err(InsertError::InvalidCatchAll),
+            },
+            None => (Some(&*filter), false),
+        };
+
+        match levels {
+            Some(levels) => {
+                let route = encode_filter(levels)?;
+                let tree = if multi_level {
+                    &mut self.parents
+                } else {
+                    &mut self.exact
+                };
+
+                tree.insert(route, index).map_err(|err| match err {
+                    // wildcards have no names, so only the same filter can conflict
+                    InsertError::Conflict { .. } => InsertError::Conflict {
+                        with: filter.clone(),
+                    },
+                    err => err,
+                })?;
+            }
+            None if self.all.is_some() => return Err(InsertError::Conflict { with: filter }),
+            None => self.all = Some(index),
+        }
+
+        let wildcard_first = matches!(filter.as_bytes()[0], b'+' | b'#');
+        self.values.push((value, wildcard_first));
+        Ok(())
+    }
+
+    /// Returns the values of every filter matching the given topic, in the order the
+    /// filters were inserted.
+    ///
+    /// Topics can't contain wildcards, so nothing matches them.
+    pub fn matches(&self, topic: &str) -> Vec<&T> {
+        if topic.is_empty() || topic.contains(|c| matches!(c, '+' | '#' | '\0')) {
+            return Vec::new();
+        }
+
+        let mut matched = Vec::new();
+
+        let mut encoded = Vec::with_capacity(topic.len() * 2);
+        for level in topic.split('/') {
+            if !encoded.is_empty() {
+                encoded.push(b'/');
+            }
+
+            encoded.push(LEVEL);
+            encoded.extend_from_slice(level.as_bytes());
+
+            // a multi-level wildcard matches its parent, so look up every prefix of levels
+            push_matches(&mut matched, &self.parents, &encoded);
+        }
+
+        push_matches(&mut matched, &self.exact, &encoded);
+        matched.extend(self.all);
+
+        // filters starting with a wildcard don't match topics reserved for the broker
+        let reserved = topic.starts_with('$');
+
+        matched.sort_unstable();
+        matched
+            .into_iter()
+            .map(|i| &self.values[i])
+            .filter(|(_, wildcard_first)| !(reserved && *wildcard_first))
+            .map(|(value, _)| value)
+            .collect()
+    }
+}
+
+// Appends the indices of all values matching `path` in the tree.
+fn push_matches(matched: &mut Vec<usize>, tree: &Node<usize, Topic>, path: &[u8]) {
+    // SAFETY: values are never mutated through the tree
+    matched.extend(
+        tree.matches(path)
+            .map(|(value, _)| unsafe { *value.value.get() }),
+    );
+}
+
+// Encodes the levels of a filter as a route, with `+` levels as parameters.
+fn encode_filter(filter: &str) -> Result<String, InsertError> {
+    let mut route = String::with_capacity(filter.len() * 2);
+    let mut params = 0;
+
+    for level in filter.split('/') {
+        if !route.is_empty() {
+            route.push('/');
+        }
+
+        match level {
+            "+" => {
+                // the parameters of a route are named `a` to `z`
+                params += 1;
+                if params > 26 {
+                    return Err(InsertError::TooManyParams);
+                }
+
+                route.push_str("+l");
+            }
+            _ if level.contains('#') => return Err(InsertError::InvalidCatchAll),
+            _ if level.contains(|c| matches!(c, '+' | '\0')) => {
+                return Err(InsertError::InvalidRoute)
+            }
+            _ => {
+                route.push(char::from(LEVEL));
+                route.push_str(level);
+            }
+        }
+    }
+
+    Ok(route)
+}
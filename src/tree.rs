@@ -1,15 +1,26 @@
+use crate::bytes;
+use crate::params::ParamsBuffer;
+use crate::syntax::{Path, Syntax};
 use crate::{InsertError, MatchError, Params};
 use alloc::borrow::ToOwned;
+use alloc::boxed::Box;
 use alloc::string::String;
 use alloc::vec;
 use alloc::vec::Vec;
 
 use core::cell::UnsafeCell;
-use core::cmp::min;
+use core::cmp::Reverse;
+use core::fmt;
+use core::marker::PhantomData;
 use core::mem;
+use core::ops::Deref;
+use core::ptr::NonNull;
+use core::slice;
 
 /// The types of nodes the tree can hold
-#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "rkyv", derive(rkyv::Archive, rkyv::Serialize))]
 pub(crate) enum NodeType {
     /// The root path
     Root,
@@ -23,34 +34,205 @@ pub(crate) enum NodeType {
 
 /// A radix tree used for URL path matching.
 ///
+/// Routes are written in the given [`Syntax`].
+///
 /// See [the crate documentation](crate) for details.
-pub struct Node<T> {
-    priority: u32,
-    wild_child: bool,
-    indices: Vec<u8>,
-    // see `at` for why an unsafe cell is needed
-    value: Option<UnsafeCell<T>>,
+pub struct Node<T, S = Path, M = ()> {
+    pub(crate) priority: u32,
+    pub(crate) wild_child: bool,
+    pub(crate) indices: Vec<u8>,
+    pub(crate) value: Option<Slot<T, M>>,
     pub(crate) param_remapping: ParamRemapping,
     pub(crate) node_type: NodeType,
     pub(crate) prefix: Vec<u8>,
     pub(crate) children: Vec<Self>,
+    pub(crate) syntax: PhantomData<S>,
 }
 
 // SAFETY: we expose `value` per rust's usual borrowing rules, so we can just delegate these traits
-unsafe impl<T: Send> Send for Node<T> {}
-unsafe impl<T: Sync> Sync for Node<T> {}
+unsafe impl<T: Send, S, M: Send> Send for Node<T, S, M> {}
+unsafe impl<T: Sync, S, M: Sync> Sync for Node<T, S, M> {}
+
+// A value stored at a stable address on the heap, so that it can be referenced by the
+// index of static routes even as the tree is restructured.
+//
+// Storing indices in the index instead would mean keeping the values outside of the tree,
+// which every other representation of it would have to follow. The extra allocation per
+// route has no measurable effect on insertion, see the `Insert` benchmark.
+//
+// This is a raw pointer rather than a `Box`, as moving a `Box` asserts unique access to
+// its contents, which would invalidate any other pointers to it.
+pub(crate) struct Slot<T, M = ()>(NonNull<Entry<T, M>>);
+
+// A value in the tree, along with the metadata of its route.
+pub(crate) struct Entry<T, M = ()> {
+    // see `at` for why an unsafe cell is needed
+    pub(crate) value: UnsafeCell<T>,
+    pub(crate) meta: M,
+}
+
+impl<T, M> Slot<T, M> {
+    pub(crate) fn new(value: T, meta: M) -> Self {
+        let value = UnsafeCell::new(value);
+        let entry = Box::into_raw(Box::new(Entry { value, meta }));
+        // SAFETY: `Box::into_raw` never returns null
+        Self(unsafe { NonNull::new_unchecked(entry) })
+    }
+
+    pub(crate) fn into_inner(self) -> (T, M) {
+        let slot = mem::ManuallyDrop::new(self);
+        // SAFETY: the pointer was created by `Box::into_raw`, and the slot is not dropped
+        let entry = unsafe { Box::from_raw(slot.0.as_ptr()) };
+        (entry.value.into_inner(), entry.meta)
+    }
+
+    fn get_mut(&mut self) -> &mut T {
+        // SAFETY: we have &mut self. This goes through the cell to avoid asserting
+        // unique access to it
+        unsafe { &mut *self.0.as_ref().value.get() }
+    }
+}
 
-impl<T> Node<T> {
+impl<T, M> Deref for Slot<T, M> {
+    type Target = Entry<T, M>;
+
+    fn deref(&self) -> &Entry<T, M> {
+        // SAFETY: the pointer is valid until the slot is dropped
+        unsafe { self.0.as_ref() }
+    }
+}
+
+impl<T, M> Drop for Slot<T, M> {
+    fn drop(&mut self) {
+        // SAFETY: the pointer was created by `Box::into_raw`
+        drop(unsafe { Box::from_raw(self.0.as_ptr()) });
+    }
+}
+
+// Fails to compile for a syntax with bytes that are not ASCII. Parameters are split at the
+// bytes of the syntax, which are only character boundaries if they are ASCII, see `Params`.
+struct AssertAscii<S>(PhantomData<S>);
+
+impl<S: Syntax> AssertAscii<S> {
+    const OK: () = assert!(
+        S::SEPARATOR.is_ascii() && S::PARAM.is_ascii() && S::CATCH_ALL.is_ascii(),
+        "the bytes of a syntax must be ASCII"
+    );
+}
+
+impl<T, S: Syntax> Node<T, S> {
     pub fn insert(&mut self, route: impl Into<String>, val: T) -> Result<(), InsertError> {
-        let route = route.into().into_bytes();
-        let (route, param_remapping) = normalize_params(route)?;
-        let mut prefix = route.as_ref();
+        self.insert_with_meta(route, val, ()).map(|_| ())
+    }
+}
+
+impl<T, S: Syntax, M> Node<T, S, M> {
+    // Inserts a route, returning the entry holding its value.
+    pub(crate) fn insert_with_meta(
+        &mut self,
+        route: impl Into<String>,
+        val: T,
+        meta: M,
+    ) -> Result<&Entry<T, M>, InsertError> {
+        let () = AssertAscii::<S>::OK;
+
+        let (route, param_remapping) = normalize_params::<S>(route.into().into_bytes())?;
+
+        // priorities are updated and nodes are split while walking the tree, so the route
+        // is checked first for a failed insertion to leave the tree untouched
+        self.check_insert(&route)?;
+
+        let slot = Slot::new(val, meta);
+        let entry = slot.0;
+        self.insert_inner(&route, param_remapping, slot)?;
+
+        // SAFETY: the slot is now owned by the tree, which is borrowed for as long as
+        // the reference
+        Ok(unsafe { entry.as_ref() })
+    }
+
+    // Checks that a normalized route can be inserted, without changing the tree.
+    fn check_insert(&self, route: &[u8]) -> Result<(), InsertError> {
+        validate_params::<S>(route)?;
+
+        // "*x" without a leading segment
+        if route.first() == Some(&S::CATCH_ALL) {
+            return Err(InsertError::InvalidCatchAll);
+        }
+
+        let mut prefix = route;
+        let mut current = self;
+
+        loop {
+            let common_prefix = bytes::common_prefix(prefix, &current.prefix);
+
+            // the node would be split, and the rest of the route inserted as a new child
+            if common_prefix < current.prefix.len() {
+                return Ok(());
+            }
+
+            // exact match, this node should be empty
+            if prefix.len() == common_prefix {
+                return match current.value {
+                    Some(_) => Err(InsertError::conflict(route, prefix, current)),
+                    None => Ok(()),
+                };
+            }
+
+            prefix = &prefix[common_prefix..];
+            let next = prefix[0];
+
+            // `/` after param
+            if current.node_type == NodeType::Param
+                && next == S::SEPARATOR
+                && current.children.len() == 1
+            {
+                current = &current.children[0];
+                continue;
+            }
+
+            if let Some(i) = bytes::find(next, &current.indices) {
+                current = &current.children[i];
+                continue;
+            }
+
+            // a new static or wildcard child would be created
+            if !current.wild_child
+                || (next != S::PARAM
+                    && next != S::CATCH_ALL
+                    && current.node_type != NodeType::CatchAll)
+            {
+                return Ok(());
+            }
+
+            // inserting a wildcard, and this node already has a wildcard child
+            current = current.children.last().unwrap();
+
+            // make sure the wildcard matches, see `insert_inner`
+            if prefix.len() < current.prefix.len()
+                || current.prefix != prefix[..current.prefix.len()]
+                || current.node_type == NodeType::CatchAll
+                || (current.prefix.len() < prefix.len()
+                    && prefix[current.prefix.len()] != S::SEPARATOR)
+            {
+                return Err(InsertError::conflict(route, prefix, current));
+            }
+        }
+    }
+
+    fn insert_inner(
+        &mut self,
+        route: &[u8],
+        param_remapping: ParamRemapping,
+        val: Slot<T, M>,
+    ) -> Result<(), InsertError> {
+        let mut prefix = route;
 
         self.priority += 1;
 
         // the tree is empty
         if self.prefix.is_empty() && self.children.is_empty() {
-            let last = self.insert_child(prefix, &route, val)?;
+            let last = self.insert_child(prefix, route, val)?;
             last.param_remapping = param_remapping;
             self.node_type = NodeType::Root;
             return Ok(());
@@ -60,10 +242,7 @@ impl<T> Node<T> {
 
         'walk: loop {
             // find the longest common prefix
-            let len = min(prefix.len(), current.prefix.len());
-            let common_prefix = (0..len)
-                .find(|&i| prefix[i] != current.prefix[i])
-                .unwrap_or(len);
+            let common_prefix = bytes::common_prefix(prefix, &current.prefix);
 
             // the common prefix is a substring of the current node's prefix, split the node
             if common_prefix < current.prefix.len() {
@@ -93,7 +272,7 @@ impl<T> Node<T> {
 
                 // `/` after param
                 if current.node_type == NodeType::Param
-                    && next == b'/'
+                    && next == S::SEPARATOR
                     && current.children.len() == 1
                 {
                     current = &mut current.children[0];
@@ -113,13 +292,16 @@ impl<T> Node<T> {
                 }
 
                 // not a wildcard and there is no matching child node, create a new one
-                if !matches!(next, b':' | b'*') && current.node_type != NodeType::CatchAll {
+                if next != S::PARAM
+                    && next != S::CATCH_ALL
+                    && current.node_type != NodeType::CatchAll
+                {
                     current.indices.push(next);
                     let mut child = current.add_child(Node::default());
                     child = current.update_child_priority(child);
 
                     // insert into the new node
-                    let last = current.children[child].insert_child(prefix, &route, val)?;
+                    let last = current.children[child].insert_child(prefix, route, val)?;
                     last.param_remapping = param_remapping;
                     return Ok(());
                 }
@@ -137,35 +319,241 @@ impl<T> Node<T> {
                         || current.node_type == NodeType::CatchAll
                         // check for longer wildcard, e.g. :name and :names
                         || (current.prefix.len() < prefix.len()
-                            && prefix[current.prefix.len()] != b'/')
+                            && prefix[current.prefix.len()] != S::SEPARATOR)
                     {
-                        return Err(InsertError::conflict(&route, prefix, current));
+                        return Err(InsertError::conflict(route, prefix, current));
                     }
 
                     continue 'walk;
                 }
 
                 // otherwise, create the wildcard node
-                let last = current.insert_child(prefix, &route, val)?;
+                let last = current.insert_child(prefix, route, val)?;
                 last.param_remapping = param_remapping;
                 return Ok(());
             }
 
             // exact match, this node should be empty
             if current.value.is_some() {
-                return Err(InsertError::conflict(&route, prefix, current));
+                return Err(InsertError::conflict(route, prefix, current));
             }
 
             // add the value to current node
-            current.value = Some(UnsafeCell::new(val));
+            current.value = Some(val);
             current.param_remapping = param_remapping;
 
             return Ok(());
         }
     }
 
+    // Returns the value of the given route, if it was inserted with the same parameter names.
+    //
+    // Unlike matching, this compares the route against the routes in the tree, so that a
+    // parameter never stands in for another route.
+    pub(crate) fn route_mut(&mut self, route: &[u8]) -> Option<&mut T> {
+        let (route, param_remapping) = normalize_params::<S>(route.to_owned()).ok()?;
+        let mut prefix = &route[..];
+        let mut current = self;
+
+        loop {
+            prefix = prefix.strip_prefix(&current.prefix[..])?;
+
+            let next = match prefix.first() {
+                Some(&next) => next,
+                None => {
+                    let value = current.value.as_mut()?;
+                    return (current.param_remapping == param_remapping).then(|| value.get_mut());
+                }
+            };
+
+            let child = match current.indices.iter().position(|&index| index == next) {
+                Some(i) => i,
+                // wildcards are always the last child
+                None if (next == S::PARAM || next == S::CATCH_ALL) && current.wild_child => {
+                    current.children.len() - 1
+                }
+                // the only child of a parameter may not have an index
+                None if current.node_type == NodeType::Param && current.children.len() == 1 => 0,
+                None => return None,
+            };
+
+            current = &mut current.children[child];
+        }
+    }
+
+    // Copies the structure of the tree, with empty values in place of its values.
+    pub(crate) fn skeleton(&self) -> Node<(), S> {
+        Node {
+            priority: self.priority,
+            wild_child: self.wild_child,
+            indices: self.indices.clone(),
+            value: self.value.as_ref().map(|_| Slot::new((), ())),
+            param_remapping: self.param_remapping.clone(),
+            node_type: self.node_type,
+            prefix: self.prefix.clone(),
+            children: self.children.iter().map(Node::skeleton).collect(),
+            syntax: PhantomData,
+        }
+    }
+
+    // Transforms every value in the tree, keeping its structure and metadata intact.
+    pub fn try_map<U, E>(self, f: &mut impl FnMut(T) -> Result<U, E>) -> Result<Node<U, S, M>, E> {
+        let value = match self.value {
+            Some(value) => {
+                let (value, meta) = value.into_inner();
+                Some(Slot::new(f(value)?, meta))
+            }
+            None => None,
+        };
+
+        let children = self
+            .children
+            .into_iter()
+            .map(|child| child.try_map(f))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Node {
+            value,
+            children,
+            priority: self.priority,
+            wild_child: self.wild_child,
+            indices: self.indices,
+            param_remapping: self.param_remapping,
+            node_type: self.node_type,
+            prefix: self.prefix,
+            syntax: PhantomData,
+        })
+    }
+
+    // Removes all values for which `f` returns `false`, pruning any nodes left without values.
+    pub fn retain(&mut self, f: &mut impl FnMut(&T) -> bool) {
+        self.retain_inner(f);
+
+        // the tree is empty
+        if self.priority == 0 {
+            *self = Node::default();
+        }
+    }
+
+    fn retain_inner(&mut self, f: &mut impl FnMut(&T) -> bool) {
+        if let Some(ref mut value) = self.value {
+            if !f(value.get_mut()) {
+                self.value = None;
+            }
+        }
+
+        for child in &mut self.children {
+            child.retain_inner(f);
+        }
+
+        // remove children without any values, keeping the indices in sync
+        let mut i = self.children.len();
+        while i > 0 {
+            i -= 1;
+
+            if self.children[i].priority != 0 {
+                continue;
+            }
+
+            self.children.remove(i);
+
+            if self.wild_child && i == self.children.len() {
+                // wildcards are always at the end
+                self.wild_child = false;
+            } else if i < self.indices.len() {
+                self.indices.remove(i);
+            }
+        }
+
+        self.priority = self
+            .children
+            .iter()
+            .map(|child| child.priority)
+            .sum::<u32>()
+            + u32::from(self.value.is_some());
+
+        // static children are ordered by priority, see `update_child_priority`
+        if self.node_type != NodeType::Param {
+            let statics = self.children.len() - usize::from(self.wild_child);
+            self.children[..statics].sort_by_key(|child| Reverse(child.priority));
+            self.indices = self.children[..statics]
+                .iter()
+                .map(|child| child.prefix[0])
+                .collect();
+        }
+
+        // a node without a value is only split if it has several children, so merge it with
+        // its only static child
+        if self.value.is_none()
+            && self.children.len() == 1
+            && !self.wild_child
+            && matches!(self.node_type, NodeType::Root | NodeType::Static)
+        {
+            let child = self.children.pop().unwrap();
+            self.prefix.extend_from_slice(&child.prefix);
+            self.indices = child.indices;
+            self.children = child.children;
+            self.wild_child = child.wild_child;
+            self.value = child.value;
+            self.param_remapping = child.param_remapping;
+        }
+    }
+
+    // Calls `f` with every route without parameters, along with its value.
+    pub(crate) fn static_routes(&self, f: &mut impl FnMut(&[u8], &Entry<T, M>)) {
+        self.static_routes_inner(&mut Vec::new(), f);
+    }
+
+    fn static_routes_inner(&self, route: &mut Vec<u8>, f: &mut impl FnMut(&[u8], &Entry<T, M>)) {
+        let len = route.len();
+        route.extend_from_slice(&self.prefix);
+
+        if let Some(value) = &self.value {
+            f(route, value);
+        }
+
+        for child in &self.children {
+            if child.node_type == NodeType::Static {
+                child.static_routes_inner(route, f);
+            }
+        }
+
+        route.truncate(len);
+    }
+
+    // Consumes the tree, returning every route in priority order along with its value
+    // and metadata.
+    pub fn into_routes(self) -> Vec<(String, T, M)> {
+        let mut routes = Vec::new();
+        self.collect_routes(Vec::new(), &mut routes);
+        routes
+    }
+
+    fn collect_routes(self, mut route: Vec<u8>, routes: &mut Vec<(String, T, M)>) {
+        route.extend_from_slice(&self.prefix);
+
+        if let Some(value) = self.value {
+            let (value, meta) = value.into_inner();
+            let original = original_route::<S>(route.clone(), &self.param_remapping);
+            routes.push((original, value, meta));
+        }
+
+        for child in self.children {
+            child.collect_routes(route.clone(), routes);
+        }
+    }
+
+    // Returns an iterator over every route in priority order, along with its value and metadata.
+    pub(crate) fn routes(&self) -> Routes<'_, T, S, M> {
+        Routes {
+            stack: vec![slice::from_ref(self).iter()],
+            lens: vec![0],
+            route: Vec::new(),
+        }
+    }
+
     // add a child node, keeping wildcards at the end
-    fn add_child(&mut self, child: Node<T>) -> usize {
+    fn add_child(&mut self, child: Self) -> usize {
         let len = self.children.len();
 
         if self.wild_child && len > 0 {
@@ -211,24 +599,24 @@ impl<T> Node<T> {
         &mut self,
         mut prefix: &[u8],
         route: &[u8],
-        val: T,
-    ) -> Result<&mut Node<T>, InsertError> {
+        val: Slot<T, M>,
+    ) -> Result<&mut Self, InsertError> {
         let mut current = self;
 
         loop {
             // search for a wildcard segment
-            let (wildcard, wildcard_index) = match find_wildcard(prefix)? {
+            let (wildcard, wildcard_index) = match find_wildcard::<S>(prefix)? {
                 Some((w, i)) => (w, i),
                 // no wildcard, simply use the current node
                 None => {
-                    current.value = Some(UnsafeCell::new(val));
+                    current.value = Some(val);
                     current.prefix = prefix.to_owned();
                     return Ok(current);
                 }
             };
 
             // regular route parameter
-            if wildcard[0] == b':' {
+            if wildcard[0] == S::PARAM {
                 // insert prefix before the current wildcard
                 if wildcard_index > 0 {
                     current.prefix = prefix[..wildcard_index].to_owned();
@@ -261,18 +649,18 @@ impl<T> Node<T> {
                 }
 
                 // otherwise we're done. Insert the value in the new leaf
-                current.value = Some(UnsafeCell::new(val));
+                current.value = Some(val);
                 return Ok(current);
 
             // catch-all route
-            } else if wildcard[0] == b'*' {
+            } else if wildcard[0] == S::CATCH_ALL {
                 // "/foo/*x/bar"
                 if wildcard_index + wildcard.len() != prefix.len() {
                     return Err(InsertError::InvalidCatchAll);
                 }
 
-                // "*x" without leading `/`
-                if prefix == route && route[0] != b'/' {
+                // "*x" without a leading segment
+                if prefix == route && wildcard_index == 0 {
                     return Err(InsertError::InvalidCatchAll);
                 }
 
@@ -285,7 +673,7 @@ impl<T> Node<T> {
                 let child = Self {
                     prefix: prefix.to_owned(),
                     node_type: NodeType::CatchAll,
-                    value: Some(UnsafeCell::new(val)),
+                    value: Some(val),
                     priority: 1,
                     ..Self::default()
                 };
@@ -299,23 +687,441 @@ impl<T> Node<T> {
     }
 }
 
-struct Skipped<'n, 'p, T> {
-    path: &'p [u8],
-    node: &'n Node<T>,
+/// Read-only access to a node of a tree, used to search it.
+///
+/// This allows other representations of the tree to share the matching logic with
+/// [`Node`], and therefore its exact semantics.
+pub(crate) trait Lookup<'n>: Copy {
+    type Value: 'n;
+
+    // The byte that separates the segments of a path.
+    const SEPARATOR: u8 = b'/';
+
+    fn prefix(self) -> &'n [u8];
+    fn indices(self) -> &'n [u8];
+    fn node_type(self) -> NodeType;
+    fn wild_child(self) -> bool;
+    fn value(self) -> Option<&'n Self::Value>;
+    fn children_len(self) -> usize;
+    fn child(self, i: usize) -> Self;
+
+    // Returns the original name of the parameter at the given position in the route
+    // ending at this node, without the leading `:`.
+    fn param_name(self, i: usize) -> &'n [u8];
+
+    // Returns the static child starting with the given byte.
+    fn static_child(self, first: u8) -> Option<Self> {
+        bytes::find(first, self.indices()).map(|i| self.child(i))
+    }
+
+    // Returns the wildcard child, which is always at the end of the list.
+    fn wildcard_child(self) -> Self {
+        self.child(self.children_len() - 1)
+    }
+
+    // Returns the only child of this node, if it has exactly one.
+    fn only_child(self) -> Option<Self> {
+        match self.children_len() {
+            1 => Some(self.child(0)),
+            _ => None,
+        }
+    }
+}
+
+impl<'n, T, S: Syntax, M> Lookup<'n> for &'n Node<T, S, M> {
+    type Value = Entry<T, M>;
+
+    const SEPARATOR: u8 = S::SEPARATOR;
+
+    fn prefix(self) -> &'n [u8] {
+        &self.prefix
+    }
+
+    fn indices(self) -> &'n [u8] {
+        &self.indices
+    }
+
+    fn node_type(self) -> NodeType {
+        self.node_type
+    }
+
+    fn wild_child(self) -> bool {
+        self.wild_child
+    }
+
+    fn value(self) -> Option<&'n Entry<T, M>> {
+        self.value.as_deref()
+    }
+
+    fn children_len(self) -> usize {
+        self.children.len()
+    }
+
+    fn child(self, i: usize) -> Self {
+        &self.children[i]
+    }
+
+    fn param_name(self, i: usize) -> &'n [u8] {
+        &self.param_remapping[i][1..]
+    }
+}
+
+/// A tree loaded from untrusted input, which is checked with [`validate`] before it is
+/// searched.
+#[cfg(any(feature = "serde", feature = "rkyv"))]
+pub(crate) trait Untrusted<'n>: Lookup<'n> {
+    // The number of parameter names in the remapping of this node.
+    fn remapping_len(self) -> usize;
+
+    // The original name of the parameter at the given position, including the leading `:`.
+    fn remapping(self, i: usize) -> &'n [u8];
+}
+
+#[cfg(any(feature = "serde", feature = "rkyv"))]
+impl<'n, T, S: Syntax, M> Untrusted<'n> for &'n Node<T, S, M> {
+    fn remapping_len(self) -> usize {
+        self.param_remapping.len()
+    }
+
+    fn remapping(self, i: usize) -> &'n [u8] {
+        &self.param_remapping[i]
+    }
+}
+
+// Checks that a tree loaded from untrusted input is one that could have been created by
+// inserting routes, which matching relies on.
+//
+// The tree is walked with an explicit stack, as it may be arbitrarily deep.
+#[cfg(any(feature = "serde", feature = "rkyv"))]
+pub(crate) fn validate<'n, N: Untrusted<'n>>(root: N) -> Result<(), &'static str> {
+    // an empty router has a root without a type, see `Node::retain`
+    let empty = root.node_type() == NodeType::Static
+        && root.prefix().is_empty()
+        && root.children_len() == 0
+        && root.value().is_none();
+
+    if root.node_type() != NodeType::Root && !empty {
+        return Err("invalid router: the root node must have type `Root`");
+    }
+
+    // the route up to the node on top of the stack, which holds every node left to check
+    // along with the length of the route and the number of parameters before it
+    let mut route = Vec::new();
+    let mut stack = vec![(root, 0, 0)];
+
+    while let Some((node, len, mut params)) = stack.pop() {
+        let prefix = node.prefix();
+        let indices = node.indices();
+        let children = node.children_len();
+
+        match node.node_type() {
+            NodeType::Param => {
+                if prefix.len() < 2 || prefix[0] != b':' || prefix.contains(&b'/') {
+                    return Err("invalid router: parameter nodes must be a single named parameter");
+                }
+
+                if node.wild_child() || children > 1 {
+                    return Err("invalid router: parameter nodes have at most one static child");
+                }
+
+                params += 1;
+            }
+            NodeType::CatchAll => {
+                if prefix.len() < 2 || prefix[0] != b'*' || prefix.contains(&b'/') {
+                    return Err("invalid router: catch-all nodes must be a single named parameter");
+                }
+
+                if node.wild_child() || !indices.is_empty() || children != 0 {
+                    return Err("invalid router: catch-all nodes cannot have children");
+                }
+
+                if node.value().is_none() {
+                    return Err("invalid router: catch-all nodes must have a value");
+                }
+            }
+            NodeType::Root | NodeType::Static => {}
+        }
+
+        if node.wild_child() && children == 0 {
+            return Err("invalid router: wildcard children must be the last child of a node");
+        }
+
+        // wildcards are always the last child
+        let statics = children - usize::from(node.wild_child());
+        for i in 0..children {
+            let valid = match node.child(i).node_type() {
+                NodeType::Static => i < statics,
+                NodeType::Param | NodeType::CatchAll => i >= statics,
+                NodeType::Root => false,
+            };
+
+            if !valid {
+                return Err("invalid router: wildcard children must be the last child of a node");
+            }
+        }
+
+        // every static child has an index, except for the only child of a parameter, which
+        // is matched without looking at its index
+        if indices.len() != statics && !(node.node_type() == NodeType::Param && indices.is_empty())
+        {
+            return Err("invalid router: the indices of a node do not match its children");
+        }
+
+        for (i, &index) in indices.iter().enumerate() {
+            if node.child(i).prefix().first() != Some(&index) || indices[..i].contains(&index) {
+                return Err("invalid router: the indices of a node do not match its children");
+            }
+        }
+
+        if node.value().is_some() {
+            if node.remapping_len() != params {
+                return Err("invalid router: the parameters of a route do not match its remapping");
+            }
+
+            for i in 0..params {
+                let param = node.remapping(i);
+                if param.len() < 2 || param[0] != b':' || core::str::from_utf8(param).is_err() {
+                    return Err("invalid router: parameter names must be valid and non-empty");
+                }
+            }
+        } else if node.remapping_len() != 0 {
+            return Err("invalid router: only nodes with values can have a parameter remapping");
+        }
+
+        route.truncate(len);
+        route.extend_from_slice(prefix);
+
+        if node.value().is_some() && core::str::from_utf8(&route).is_err() {
+            return Err("invalid router: routes must be valid UTF-8");
+        }
+
+        for i in 0..children {
+            stack.push((node.child(i), route.len(), params));
+        }
+    }
+
+    Ok(())
+}
+
+struct Skipped<N> {
+    // the length of the rest of the path at the node, which is a suffix of the full path
+    path: usize,
+    node: N,
     params: usize,
 }
 
+impl<N> Skipped<N> {
+    // Returns the rest of the path at the node.
+    fn path<'p>(&self, full_path: &'p [u8]) -> &'p [u8] {
+        &full_path[full_path.len() - self.path..]
+    }
+}
+
+/// Reusable buffers for matching paths without allocating, see [`Router::at_with`](crate::Router::at_with).
+///
+/// The buffers grow to fit the largest match they were used for, after which matching
+/// never allocates. A set of buffers borrows the router it is used with, and its type
+/// depends on the kind of router, which is inferred.
+///
+/// ```rust
+/// use matchit::{MatchScratch, Router};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut router = Router::new();
+/// router.insert("/users/:id", "A User")?;
+///
+/// let mut scratch = MatchScratch::new();
+/// for path in ["/users/1", "/users/2"] {
+///     let matched = router.at_with(&mut scratch, path)?;
+///     assert_eq!(*matched.value, "A User");
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct MatchScratch<'n, N> {
+    params: ParamsBuffer<'n>,
+    skipped: Vec<Skipped<N>>,
+}
+
+impl<N> MatchScratch<'_, N> {
+    /// Creates a new set of empty buffers.
+    pub fn new() -> Self {
+        Self {
+            params: ParamsBuffer::default(),
+            skipped: Vec::new(),
+        }
+    }
+}
+
+impl<N> Default for MatchScratch<'_, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N> fmt::Debug for MatchScratch<'_, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MatchScratch").finish_non_exhaustive()
+    }
+}
+
+/// An iterator over mutable references to all values in the tree, in priority order.
+pub(crate) struct ValuesMut<'n, T, M> {
+    stack: Vec<slice::IterMut<'n, Node<T, Path, M>>>,
+}
+
+impl<'n, T, M> ValuesMut<'n, T, M> {
+    pub fn new(root: &'n mut Node<T, Path, M>) -> Self {
+        Self {
+            stack: vec![slice::from_mut(root).iter_mut()],
+        }
+    }
+}
+
+impl<'n, T, M> Iterator for ValuesMut<'n, T, M> {
+    type Item = &'n mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let node = match self.stack.last_mut()?.next() {
+                Some(node) => node,
+                None => {
+                    self.stack.pop();
+                    continue;
+                }
+            };
+
+            let Node {
+                value, children, ..
+            } = node;
+
+            self.stack.push(children.iter_mut());
+
+            if let Some(value) = value {
+                return Some(value.get_mut());
+            }
+        }
+    }
+}
+
+/// An iterator over all routes in the tree along with their values, in priority order.
+pub(crate) struct Routes<'n, T, S, M> {
+    // the children left to visit at each level
+    stack: Vec<slice::Iter<'n, Node<T, S, M>>>,
+    // the length of the route of the parent at each level
+    lens: Vec<usize>,
+    route: Vec<u8>,
+}
+
+impl<'n, T, S: Syntax, M> Iterator for Routes<'n, T, S, M> {
+    type Item = (String, &'n Entry<T, M>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let node = match self.stack.last_mut()?.next() {
+                Some(node) => node,
+                None => {
+                    self.stack.pop();
+                    self.lens.pop();
+                    continue;
+                }
+            };
+
+            self.route.truncate(self.lens[self.lens.len() - 1]);
+            self.route.extend_from_slice(&node.prefix);
+            self.stack.push(node.children.iter());
+            self.lens.push(self.route.len());
+
+            if let Some(value) = &node.value {
+                let route = original_route::<S>(self.route.clone(), &node.param_remapping);
+                return Some((route, value));
+            }
+        }
+    }
+}
+
+/// A node with a value that matched part of a path, see [`at_or_ancestor`] and [`at_prefix`].
+pub(crate) struct Ancestor<'n, 'p, N: Lookup<'n>> {
+    value: &'n N::Value,
+    params: Params<'n, 'p>,
+    consumed: usize,
+}
+
+impl<'n, 'p, N: Lookup<'n>> Ancestor<'n, 'p, N> {
+    // Records `node` as the ancestor if it has a value and is deeper than the current one.
+    fn update(ancestor: &mut Option<Self>, node: N, params: &Params<'n, 'p>, consumed: usize) {
+        let value = match node.value() {
+            Some(value) => value,
+            None => return,
+        };
+
+        if matches!(ancestor, Some(ancestor) if ancestor.consumed >= consumed) {
+            return;
+        }
+
+        // remap parameter keys
+        let mut params = params.clone();
+        params.for_each_key_mut(|(i, key)| *key = node.param_name(i));
+
+        *ancestor = Some(Ancestor {
+            value,
+            params,
+            consumed,
+        });
+    }
+}
+
+/// An iterator over all values matching a path, returned by [`matches`].
+pub(crate) struct Matches<'n, 'p, N: Lookup<'n>> {
+    root: Option<N>,
+    full_path: &'p [u8],
+    params: Params<'n, 'p>,
+    skipped_nodes: Vec<Skipped<N>>,
+}
+
+impl<'n, 'p, N: Lookup<'n>> Iterator for Matches<'n, 'p, N> {
+    type Item = (&'n N::Value, Params<'n, 'p>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            // start at the root, and then resume from any wildcard nodes we skipped
+            let (node, path, backtracking) = match self.root.take() {
+                Some(root) => (root, self.full_path, false),
+                None => {
+                    let skipped = self.skipped_nodes.pop()?;
+                    self.params.truncate(skipped.params);
+                    (skipped.node, skipped.path(self.full_path), true)
+                }
+            };
+
+            // unlike `at`, errors such as a missing trailing slash only mean that this
+            // branch has no match, so we keep searching
+            if let Ok(value) = walk(
+                node,
+                self.full_path,
+                path,
+                backtracking,
+                &mut self.params,
+                &mut self.skipped_nodes,
+                None,
+            ) {
+                return Some((value, self.params.clone()));
+            }
+        }
+    }
+}
+
 #[rustfmt::skip]
 macro_rules! backtracker {
-    ($skipped_nodes:ident, $path:ident, $current:ident, $params:ident, $backtracking:ident, $walk:lifetime) => {
+    ($skipped_nodes:ident, $full_path:ident, $path:ident, $current:ident, $params:ident, $backtracking:ident, $walk:lifetime) => {
         macro_rules! try_backtrack {
             () => {
                 // try backtracking to any matching wildcard nodes we skipped while traversing
                 // the tree
                 while let Some(skipped) = $skipped_nodes.pop() {
-                    if skipped.path.ends_with($path) {
-                        $path = skipped.path;
-                        $current = &skipped.node;
+                    if skipped.path($full_path).ends_with($path) {
+                        $path = skipped.path($full_path);
+                        $current = skipped.node;
                         $params.truncate(skipped.params);
                         $backtracking = true;
                         continue $walk;
@@ -326,223 +1132,380 @@ macro_rules! backtracker {
     };
 }
 
-impl<T> Node<T> {
+impl<T, S: Syntax, M> Node<T, S, M> {
     // it's a bit sad that we have to introduce unsafe here but rust doesn't really have a way
     // to abstract over mutability, so `UnsafeCell` lets us avoid having to duplicate logic between
     // `at` and `at_mut`
-    pub fn at<'n, 'p>(
+    pub(crate) fn at<'n, 'p>(
         &'n self,
         full_path: &'p [u8],
-    ) -> Result<(&'n UnsafeCell<T>, Params<'n, 'p>), MatchError> {
-        let mut current = self;
-        let mut path = full_path;
-        let mut backtracking = false;
-        let mut params = Params::new();
-        let mut skipped_nodes = Vec::new();
+    ) -> Result<(&'n Entry<T, M>, Params<'n, 'p>), MatchError> {
+        at(self, full_path)
+    }
 
-        'walk: loop {
-            backtracker!(skipped_nodes, path, current, params, backtracking, 'walk);
-
-            // the path is longer than this node's prefix, we are expecting a child node
-            if path.len() > current.prefix.len() {
-                let (prefix, rest) = path.split_at(current.prefix.len());
-
-                // the prefix matches
-                if prefix == current.prefix {
-                    let first = rest[0];
-                    let consumed = path;
-                    path = rest;
-
-                    // try searching for a matching static child unless we are currently
-                    // backtracking, which would mean we already traversed them
-                    if !backtracking {
-                        if let Some(i) = current.indices.iter().position(|&c| c == first) {
-                            // keep track of wildcard routes we skipped to backtrack to later if
-                            // we don't find a math
-                            if current.wild_child {
-                                skipped_nodes.push(Skipped {
-                                    path: consumed,
-                                    node: current,
-                                    params: params.len(),
-                                });
-                            }
+    // Like `at`, but if no route matches, returns the deepest node with a value that matched
+    // a segment-aligned prefix of the path, along with the length of that prefix.
+    pub(crate) fn at_or_ancestor<'n, 'p>(
+        &'n self,
+        full_path: &'p [u8],
+    ) -> Result<(&'n Entry<T, M>, Params<'n, 'p>, usize), MatchError> {
+        at_or_ancestor(self, full_path)
+    }
 
-                            // child won't match because of an extra trailing slash
-                            if path == b"/"
-                                && current.children[i].prefix != b"/"
-                                && current.value.is_some()
-                            {
-                                return Err(MatchError::ExtraTrailingSlash);
-                            }
+    // Returns the longest route matching a segment-aligned prefix of the path, along with
+    // the length of that prefix.
+    pub(crate) fn at_prefix<'n, 'p>(
+        &'n self,
+        full_path: &'p [u8],
+    ) -> Result<(&'n Entry<T, M>, Params<'n, 'p>, usize), MatchError> {
+        at_prefix(self, full_path)
+    }
 
-                            // continue with the child node
-                            current = &current.children[i];
-                            continue 'walk;
-                        }
+    // Returns an iterator over all values matching the given path, in priority order.
+    pub(crate) fn matches<'n, 'p>(&'n self, full_path: &'p [u8]) -> Matches<'n, 'p, &'n Self> {
+        matches(self, full_path)
+    }
+}
+
+// Searches the tree for a value matching the given path.
+pub(crate) fn at<'n, 'p, N: Lookup<'n>>(
+    root: N,
+    full_path: &'p [u8],
+) -> Result<(&'n N::Value, Params<'n, 'p>), MatchError> {
+    let mut params = Params::new();
+
+    let value = walk(
+        root,
+        full_path,
+        full_path,
+        false,
+        &mut params,
+        &mut Vec::new(),
+        None,
+    )?;
+    Ok((value, params))
+}
+
+// Like `at`, but uses the buffers in `scratch` instead of allocating.
+pub(crate) fn at_with<'n, 'p, 's, N: Lookup<'n>>(
+    root: N,
+    scratch: &'s mut MatchScratch<'n, N>,
+    full_path: &'p [u8],
+) -> Result<(&'n N::Value, Params<'s, 's>), MatchError>
+where
+    'p: 's,
+{
+    let mut params = Params::with_buffer(&mut scratch.params, full_path);
+
+    let mut skipped_nodes = mem::take(&mut scratch.skipped);
+    skipped_nodes.clear();
+
+    let result = walk(
+        root,
+        full_path,
+        full_path,
+        false,
+        &mut params,
+        &mut skipped_nodes,
+        None,
+    );
+
+    scratch.skipped = skipped_nodes;
+    let params = params.into_buffer(&mut scratch.params);
+    result.map(|value| (value, params))
+}
+
+// See `Node::at_or_ancestor`.
+pub(crate) fn at_or_ancestor<'n, 'p, N: Lookup<'n>>(
+    root: N,
+    full_path: &'p [u8],
+) -> Result<(&'n N::Value, Params<'n, 'p>, usize), MatchError> {
+    match walk_with_ancestor(root, full_path) {
+        (Ok((value, params)), _) => Ok((value, params, full_path.len())),
+        (Err(MatchError::NotFound), Some(ancestor)) => {
+            Ok((ancestor.value, ancestor.params, ancestor.consumed))
+        }
+        (Err(e), _) => Err(e),
+    }
+}
+
+// See `Node::at_prefix`.
+pub(crate) fn at_prefix<'n, 'p, N: Lookup<'n>>(
+    root: N,
+    full_path: &'p [u8],
+) -> Result<(&'n N::Value, Params<'n, 'p>, usize), MatchError> {
+    match walk_with_ancestor(root, full_path) {
+        (Ok((value, params)), _) => Ok((value, params, full_path.len())),
+        // unlike `at_or_ancestor`, a trailing slash mismatch still has a matching prefix
+        (Err(_), Some(ancestor)) => Ok((ancestor.value, ancestor.params, ancestor.consumed)),
+        (Err(_), None) => Err(MatchError::NotFound),
+    }
+}
+
+// Searches for a value matching `full_path`, keeping track of the deepest node with a
+// value that matched a segment-aligned prefix of the path.
+#[allow(clippy::type_complexity)]
+fn walk_with_ancestor<'n, 'p, N: Lookup<'n>>(
+    root: N,
+    full_path: &'p [u8],
+) -> (
+    Result<(&'n N::Value, Params<'n, 'p>), MatchError>,
+    Option<Ancestor<'n, 'p, N>>,
+) {
+    let mut params = Params::new();
+
+    let mut ancestor = None;
+
+    let result = walk(
+        root,
+        full_path,
+        full_path,
+        false,
+        &mut params,
+        &mut Vec::new(),
+        Some(&mut ancestor),
+    );
+
+    (result.map(|value| (value, params)), ancestor)
+}
+
+// See `Node::matches`.
+pub(crate) fn matches<'n, 'p, N: Lookup<'n>>(root: N, full_path: &'p [u8]) -> Matches<'n, 'p, N> {
+    Matches {
+        root: Some(root),
+        full_path,
+        params: Params::new(),
+        skipped_nodes: Vec::new(),
+    }
+}
+
+// Searches for a value matching `path`, starting at the given node.
+//
+// Any wildcard nodes skipped along the way that were not backtracked to are left in
+// `skipped_nodes`, so that the search can be resumed to find lower priority matches.
+fn walk<'n, 'p, N: Lookup<'n>>(
+    root: N,
+    full_path: &'p [u8],
+    mut path: &'p [u8],
+    mut backtracking: bool,
+    params: &mut Params<'n, 'p>,
+    skipped_nodes: &mut Vec<Skipped<N>>,
+    mut ancestor: Option<&mut Option<Ancestor<'n, 'p, N>>>,
+) -> Result<&'n N::Value, MatchError> {
+    let sep = N::SEPARATOR;
+    let mut current = root;
+
+    'walk: loop {
+        backtracker!(skipped_nodes, full_path, path, current, params, backtracking, 'walk);
+
+        // the path is longer than this node's prefix, we are expecting a child node
+        if path.len() > current.prefix().len() {
+            let (prefix, rest) = path.split_at(current.prefix().len());
+
+            // the prefix matches
+            if bytes::eq(prefix, current.prefix()) {
+                let first = rest[0];
+                let consumed = path;
+                path = rest;
+
+                // remember this node in case nothing deeper matches
+                if let Some(ancestor) = ancestor.as_deref_mut() {
+                    if first == sep || current.prefix().last() == Some(&sep) {
+                        Ancestor::update(ancestor, current, params, full_path.len() - path.len());
                     }
+                }
 
-                    // we didn't find a match and there are no children with wildcards, there is no match
-                    if !current.wild_child {
-                        // extra trailing slash
-                        if path == b"/" && current.value.is_some() {
-                            return Err(MatchError::ExtraTrailingSlash);
+                // try searching for a matching static child unless we are currently
+                // backtracking, which would mean we already traversed them
+                if !backtracking {
+                    if let Some(child) = current.static_child(first) {
+                        // keep track of wildcard routes we skipped to backtrack to later if
+                        // we don't find a math
+                        if current.wild_child() {
+                            skipped_nodes.push(Skipped {
+                                path: consumed.len(),
+                                node: current,
+                                params: params.len(),
+                            });
                         }
 
-                        // try backtracking
-                        if path != b"/" {
-                            try_backtrack!();
+                        // child won't match because of an extra trailing slash
+                        if path == [sep] && child.prefix() != [sep] && current.value().is_some() {
+                            return Err(MatchError::ExtraTrailingSlash);
                         }
 
-                        // nothing found
-                        return Err(MatchError::NotFound);
+                        // continue with the child node
+                        current = child;
+                        continue 'walk;
                     }
+                }
 
-                    // handle the wildcard child, which is always at the end of the list
-                    current = current.children.last().unwrap();
-
-                    match current.node_type {
-                        NodeType::Param => {
-                            // check if there are more segments in the path other than this parameter
-                            match path.iter().position(|&c| c == b'/') {
-                                Some(i) => {
-                                    let (param, rest) = path.split_at(i);
-
-                                    if let [child] = current.children.as_slice() {
-                                        // child won't match because of an extra trailing slash
-                                        if rest == b"/"
-                                            && child.prefix != b"/"
-                                            && current.value.is_some()
-                                        {
-                                            return Err(MatchError::ExtraTrailingSlash);
-                                        }
-
-                                        // store the parameter value
-                                        params.push(&current.prefix[1..], param);
-
-                                        // continue with the child node
-                                        path = rest;
-                                        current = child;
-                                        backtracking = false;
-                                        continue 'walk;
-                                    }
+                // we didn't find a match and there are no children with wildcards, there is no match
+                if !current.wild_child() {
+                    // extra trailing slash
+                    if path == [sep] && current.value().is_some() {
+                        return Err(MatchError::ExtraTrailingSlash);
+                    }
 
-                                    // this node has no children yet the path has more segments...
-                                    // either the path has an extra trailing slash or there is no match
-                                    if path.len() == i + 1 {
-                                        return Err(MatchError::ExtraTrailingSlash);
-                                    }
+                    // try backtracking
+                    if path != [sep] {
+                        try_backtrack!();
+                    }
 
-                                    // try backtracking
-                                    if path != b"/" {
-                                        try_backtrack!();
-                                    }
+                    // nothing found
+                    return Err(MatchError::NotFound);
+                }
+
+                // handle the wildcard child, which is always at the end of the list
+                current = current.wildcard_child();
+
+                match current.node_type() {
+                    NodeType::Param => {
+                        // check if there are more segments in the path other than this parameter
+                        match bytes::find(sep, path) {
+                            Some(i) => {
+                                let (param, rest) = path.split_at(i);
+
+                                // store the parameter value
+                                params.push(&current.prefix()[1..], param);
 
-                                    return Err(MatchError::NotFound);
+                                // remember this node in case nothing deeper matches
+                                if let Some(ancestor) = ancestor.as_deref_mut() {
+                                    let consumed = full_path.len() - rest.len();
+                                    Ancestor::update(ancestor, current, params, consumed);
                                 }
-                                // this is the last path segment
-                                None => {
-                                    // store the parameter value
-                                    params.push(&current.prefix[1..], path);
-
-                                    // found the matching value
-                                    if let Some(ref value) = current.value {
-                                        // remap parameter keys
-                                        params.for_each_key_mut(|(i, key)| {
-                                            *key = &current.param_remapping[i][1..]
-                                        });
-
-                                        return Ok((value, params));
-                                    }
 
-                                    // check the child node in case the path is missing a trailing slash
-                                    if let [child] = current.children.as_slice() {
-                                        current = child;
-
-                                        if (current.prefix == b"/" && current.value.is_some())
-                                            || (current.prefix.is_empty()
-                                                && current.indices == b"/")
-                                        {
-                                            return Err(MatchError::MissingTrailingSlash);
-                                        }
-
-                                        // no match, try backtracking
-                                        if path != b"/" {
-                                            try_backtrack!();
-                                        }
+                                if let Some(child) = current.only_child() {
+                                    // child won't match because of an extra trailing slash
+                                    if rest == [sep]
+                                        && child.prefix() != [sep]
+                                        && current.value().is_some()
+                                    {
+                                        return Err(MatchError::ExtraTrailingSlash);
                                     }
 
-                                    // this node doesn't have the value, no match
-                                    return Err(MatchError::NotFound);
+                                    // continue with the child node
+                                    path = rest;
+                                    current = child;
+                                    backtracking = false;
+                                    continue 'walk;
                                 }
+
+                                // this node has no children yet the path has more segments...
+                                // either the path has an extra trailing slash or there is no match
+                                if path.len() == i + 1 {
+                                    return Err(MatchError::ExtraTrailingSlash);
+                                }
+
+                                // try backtracking
+                                if path != [sep] {
+                                    try_backtrack!();
+                                }
+
+                                return Err(MatchError::NotFound);
                             }
-                        }
-                        NodeType::CatchAll => {
-                            // catch all segments are only allowed at the end of the route,
-                            // either this node has the value or there is no match
-                            return match current.value {
-                                Some(ref value) => {
+                            // this is the last path segment
+                            None => {
+                                // store the parameter value
+                                params.push(&current.prefix()[1..], path);
+
+                                // found the matching value
+                                if let Some(value) = current.value() {
                                     // remap parameter keys
-                                    params.for_each_key_mut(|(i, key)| {
-                                        *key = &current.param_remapping[i][1..]
-                                    });
+                                    params
+                                        .for_each_key_mut(|(i, key)| *key = current.param_name(i));
 
-                                    // store the final catch-all parameter
-                                    params.push(&current.prefix[1..], path);
+                                    return Ok(value);
+                                }
+
+                                // check the child node in case the path is missing a trailing slash
+                                if let Some(child) = current.only_child() {
+                                    current = child;
+
+                                    if (current.prefix() == [sep] && current.value().is_some())
+                                        || (current.prefix().is_empty()
+                                            && current.indices() == [sep])
+                                    {
+                                        return Err(MatchError::MissingTrailingSlash);
+                                    }
 
-                                    Ok((value, params))
+                                    // no match, try backtracking
+                                    if path != [sep] {
+                                        try_backtrack!();
+                                    }
                                 }
-                                None => Err(MatchError::NotFound),
-                            };
+
+                                // this node doesn't have the value, no match
+                                return Err(MatchError::NotFound);
+                            }
                         }
-                        _ => unreachable!(),
                     }
+                    NodeType::CatchAll => {
+                        // catch all segments are only allowed at the end of the route,
+                        // either this node has the value or there is no match
+                        return match current.value() {
+                            Some(value) => {
+                                // remap parameter keys
+                                params.for_each_key_mut(|(i, key)| *key = current.param_name(i));
+
+                                // store the final catch-all parameter
+                                params.push(&current.prefix()[1..], path);
+
+                                Ok(value)
+                            }
+                            None => Err(MatchError::NotFound),
+                        };
+                    }
+                    _ => unreachable!(),
                 }
             }
+        }
 
-            // this is it, we should have reached the node containing the value
-            if path == current.prefix {
-                if let Some(ref value) = current.value {
-                    // remap parameter keys
-                    params.for_each_key_mut(|(i, key)| *key = &current.param_remapping[i][1..]);
-                    return Ok((value, params));
-                }
+        // this is it, we should have reached the node containing the value
+        if bytes::eq(path, current.prefix()) {
+            if let Some(value) = current.value() {
+                // remap parameter keys
+                params.for_each_key_mut(|(i, key)| *key = current.param_name(i));
+                return Ok(value);
+            }
 
-                // nope, try backtracking
-                try_backtrack!();
+            // nope, try backtracking
+            try_backtrack!();
 
-                // TODO: does this *always* means there is an extra trailing slash?
-                if path == b"/" && current.wild_child && current.node_type != NodeType::Root {
-                    return Err(MatchError::unsure(full_path));
-                }
+            // TODO: does this *always* means there is an extra trailing slash?
+            if path == [sep] && current.wild_child() && current.node_type() != NodeType::Root {
+                return Err(MatchError::unsure(full_path, sep));
+            }
 
-                if !backtracking {
-                    // check if the path is missing a trailing slash
-                    if let Some(i) = current.indices.iter().position(|&c| c == b'/') {
-                        current = &current.children[i];
+            if !backtracking {
+                // check if the path is missing a trailing slash
+                if let Some(child) = current.static_child(sep) {
+                    current = child;
 
-                        if current.prefix.len() == 1 && current.value.is_some() {
-                            return Err(MatchError::MissingTrailingSlash);
-                        }
+                    if current.prefix().len() == 1 && current.value().is_some() {
+                        return Err(MatchError::MissingTrailingSlash);
                     }
                 }
-
-                return Err(MatchError::NotFound);
             }
 
-            // nothing matches, check for a missing trailing slash
-            if current.prefix.split_last() == Some((&b'/', path)) && current.value.is_some() {
-                return Err(MatchError::MissingTrailingSlash);
-            }
+            return Err(MatchError::NotFound);
+        }
 
-            // last chance, try backtracking
-            if path != b"/" {
-                try_backtrack!();
-            }
+        // nothing matches, check for a missing trailing slash
+        if current.prefix().split_last() == Some((&sep, path)) && current.value().is_some() {
+            return Err(MatchError::MissingTrailingSlash);
+        }
 
-            return Err(MatchError::NotFound);
+        // last chance, try backtracking
+        if path != [sep] {
+            try_backtrack!();
         }
+
+        return Err(MatchError::NotFound);
     }
+}
 
+impl<T, S: Syntax, M> Node<T, S, M> {
     #[cfg(feature = "__test_helpers")]
     pub fn check_priorities(&self) -> Result<u32, (u32, u32)> {
         let mut priority: u32 = 0;
@@ -567,7 +1530,9 @@ type ParamRemapping = Vec<Vec<u8>>;
 
 /// Returns `path` with normalized route parameters, and a parameter remapping
 /// to store at the leaf node for this route.
-fn normalize_params(mut path: Vec<u8>) -> Result<(Vec<u8>, ParamRemapping), InsertError> {
+pub(crate) fn normalize_params<S: Syntax>(
+    mut path: Vec<u8>,
+) -> Result<(Vec<u8>, ParamRemapping), InsertError> {
     let mut start = 0;
     let mut original = ParamRemapping::new();
 
@@ -575,7 +1540,7 @@ fn normalize_params(mut path: Vec<u8>) -> Result<(Vec<u8>, ParamRemapping), Inse
     let mut next = b'a';
 
     loop {
-        let (wildcard, mut wildcard_index) = match find_wildcard(&path[start..])? {
+        let (wildcard, mut wildcard_index) = match find_wildcard::<S>(&path[start..])? {
             Some((w, i)) => (w, i),
             None => return Ok((path, original)),
         };
@@ -586,17 +1551,22 @@ fn normalize_params(mut path: Vec<u8>) -> Result<(Vec<u8>, ParamRemapping), Inse
         }
 
         // don't need to normalize catch-all parameters
-        if wildcard[0] == b'*' {
+        if wildcard[0] == S::CATCH_ALL {
             start += wildcard_index + wildcard.len();
             continue;
         }
 
         wildcard_index += start;
 
+        // parameters are named `a` through `z`, see `validate_params`
+        if next > b'z' {
+            return Err(InsertError::TooManyParams);
+        }
+
         // normalize the parameter
         let removed = path.splice(
             (wildcard_index)..(wildcard_index + wildcard.len()),
-            vec![b':', next],
+            vec![S::PARAM, next],
         );
 
         // remember the original name for remappings
@@ -604,22 +1574,25 @@ fn normalize_params(mut path: Vec<u8>) -> Result<(Vec<u8>, ParamRemapping), Inse
 
         // get the next key
         next += 1;
-        if next > b'z' {
-            panic!("too many route parameters");
-        }
 
         start = wildcard_index + 2;
     }
 }
 
+// Restores the original parameter names of a route, see `denormalize_params`.
+fn original_route<S: Syntax>(mut route: Vec<u8>, params: &ParamRemapping) -> String {
+    denormalize_params::<S>(&mut route, params);
+    String::from_utf8(route).unwrap()
+}
+
 /// Restores `route` to it's original, denormalized form.
-pub(crate) fn denormalize_params(route: &mut Vec<u8>, params: &ParamRemapping) {
+pub(crate) fn denormalize_params<S: Syntax>(route: &mut Vec<u8>, params: &ParamRemapping) {
     let mut start = 0;
     let mut i = 0;
 
     loop {
         // find the next wildcard
-        let (wildcard, mut wildcard_index) = match find_wildcard(&route[start..]).unwrap() {
+        let (wildcard, mut wildcard_index) = match find_wildcard::<S>(&route[start..]).unwrap() {
             Some((w, i)) => (w, i),
             None => return,
         };
@@ -642,18 +1615,94 @@ pub(crate) fn denormalize_params(route: &mut Vec<u8>, params: &ParamRemapping) {
     }
 }
 
-// Searches for a wildcard segment and checks the path for invalid characters.
-fn find_wildcard(path: &[u8]) -> Result<Option<(&[u8], usize)>, InsertError> {
+/// Checks that `route` is a well-formed route without inserting it anywhere.
+///
+/// Routes must start with `/`, and may only contain characters allowed in a URL path
+/// by RFC 3986. Non-ASCII characters are allowed as they are commonly used in IRIs.
+pub(crate) fn validate_route(route: &[u8]) -> Result<(), InsertError> {
+    match route.first() {
+        Some(b'/') => {}
+        // "*x" without leading `/`
+        Some(b'*') => return Err(InsertError::InvalidCatchAll),
+        _ => return Err(InsertError::InvalidRoute),
+    }
+
+    let mut i = 0;
+    while i < route.len() {
+        match route[i] {
+            // percent-encoded octets must be followed by two hex digits
+            b'%' => match route.get(i + 1..i + 3) {
+                Some([a, b]) if a.is_ascii_hexdigit() && b.is_ascii_hexdigit() => i += 2,
+                _ => return Err(InsertError::InvalidRoute),
+            },
+            c if is_path_char(c) => {}
+            _ => return Err(InsertError::InvalidRoute),
+        }
+
+        i += 1;
+    }
+
+    validate_params::<Path>(route)
+}
+
+/// Checks that the parameters of `route` are named, that catch-all parameters are
+/// at the end of the route, and that there are at most 26 named parameters.
+pub(crate) fn validate_params<S: Syntax + ?Sized>(route: &[u8]) -> Result<(), InsertError> {
+    let mut start = 0;
+    let mut params = 0;
+    while let Some((wildcard, i)) = find_wildcard::<S>(&route[start..])? {
+        if wildcard.len() < 2 {
+            return Err(InsertError::UnnamedParam);
+        }
+
+        start += i + wildcard.len();
+
+        if wildcard[0] == S::CATCH_ALL {
+            if start != route.len() {
+                return Err(InsertError::InvalidCatchAll);
+            }
+
+            continue;
+        }
+
+        // parameters are normalized to a single letter, see `normalize_params`
+        params += 1;
+        if params > 26 {
+            return Err(InsertError::TooManyParams);
+        }
+    }
+
+    Ok(())
+}
+
+// Returns `true` if `c` is allowed to appear in a URL path, besides percent-encodings.
+fn is_path_char(c: u8) -> bool {
+    matches!(c,
+        // unreserved
+        b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~'
+        // sub-delims
+        | b'!' | b'$' | b'&' | b'\'' | b'(' | b')' | b'*' | b'+' | b',' | b';' | b'='
+        // path separators
+        | b':' | b'@' | b'/'
+        // non-ASCII
+        | 0x80..=0xff
+    )
+}
+
+// Searches for a wildcard segment, checking that it is the only one in its segment.
+fn find_wildcard<S: Syntax + ?Sized>(path: &[u8]) -> Result<Option<(&[u8], usize)>, InsertError> {
     for (start, &c) in path.iter().enumerate() {
         // a wildcard starts with ':' (param) or '*' (catch-all)
-        if c != b':' && c != b'*' {
+        if c != S::PARAM && c != S::CATCH_ALL {
             continue;
         }
 
         for (end, &c) in path[start + 1..].iter().enumerate() {
             match c {
-                b'/' => return Ok(Some((&path[start..start + 1 + end], start))),
-                b':' | b'*' => return Err(InsertError::TooManyParams),
+                c if c == S::SEPARATOR => return Ok(Some((&path[start..start + 1 + end], start))),
+                c if c == S::PARAM || c == S::CATCH_ALL => {
+                    return Err(InsertError::TooManySegmentParams)
+                }
                 _ => {}
             }
         }
@@ -664,32 +1713,40 @@ fn find_wildcard(path: &[u8]) -> Result<Option<(&[u8], usize)>, InsertError> {
     Ok(None)
 }
 
-impl<T> Clone for Node<T>
+impl<T, S, M> Clone for Node<T, S, M>
 where
     T: Clone,
+    M: Clone,
 {
     fn clone(&self) -> Self {
         let value = self.value.as_ref().map(|value| {
             // safety: we only expose &mut T through &mut self
-            let value = unsafe { &*value.get() };
-            UnsafeCell::new(value.clone())
+            let inner = unsafe { &*value.value.get() };
+            Slot::new(inner.clone(), value.meta.clone())
         });
 
         Self {
             value,
             prefix: self.prefix.clone(),
             wild_child: self.wild_child,
-            node_type: self.node_type.clone(),
+            node_type: self.node_type,
             indices: self.indices.clone(),
             children: self.children.clone(),
             param_remapping: self.param_remapping.clone(),
             priority: self.priority,
+            syntax: PhantomData,
         }
     }
 }
 
-impl<T> Default for Node<T> {
+impl<T, S, M> Default for Node<T, S, M> {
     fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, S, M> Node<T, S, M> {
+    pub const fn new() -> Self {
         Self {
             param_remapping: ParamRemapping::new(),
             prefix: Vec::new(),
@@ -699,6 +1756,7 @@ impl<T> Default for Node<T> {
             children: Vec::new(),
             value: None,
             priority: 0,
+            syntax: PhantomData,
         }
     }
 }
@@ -708,10 +1766,10 @@ const _: () = {
     use std::fmt::{self, Debug, Formatter};
 
     // visualize the tree structure when debugging
-    impl<T: Debug> Debug for Node<T> {
+    impl<T: Debug, S, M> Debug for Node<T, S, M> {
         fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
             // safety: we only expose &mut T through &mut self
-            let value = unsafe { self.value.as_ref().map(|x| &*x.get()) };
+            let value = unsafe { self.value.as_ref().map(|x| &*x.value.get()) };
 
             let indices = self
                 .indices
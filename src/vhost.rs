@@ -0,0 +1,170 @@
+use crate::{HostRouter, InsertError, Match, MatchError, Params, Router};
+use alloc::format;
+use alloc::string::String;
+
+/// A router that matches the host of a request along with its path.
+///
+/// Routes are a [`HostRouter`] host followed by a [`Router`] path, like
+/// `api.example.com/users/:id`, or just a path like `/users/:id`, which matches any host.
+/// The routes of every matching host are tried first, in the same priority order as
+/// [`HostRouter`], falling back to the routes without a host if none of them match the
+/// path. The parameters of the host come before the parameters
+/// of the path.
+///
+/// ```rust
+/// use matchit::VirtualRouter;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut router = VirtualRouter::new();
+/// router.insert("api.example.com/users/:id", "API User")?;
+/// router.insert(":tenant.example.com/users/:id", "Tenant User")?;
+/// router.insert("/health", "Health Check")?;
+///
+/// let matched = router.at("acme.example.com", "/users/978")?;
+/// assert_eq!(*matched.value, "Tenant User");
+/// assert_eq!(matched.params.get("tenant"), Some("acme"));
+/// assert_eq!(matched.params.get("id"), Some("978"));
+///
+/// assert_eq!(*router.at("api.example.com", "/users/1")?.value, "API User");
+/// assert_eq!(*router.at("api.example.com", "/health")?.value, "Health Check");
+/// # Ok(())
+/// # }
+/// ```
+pub struct VirtualRouter<T> {
+    hosts: HostRouter<Router<T>>,
+    // routes without a host
+    any: Router<T>,
+}
+
+impl<T> Default for VirtualRouter<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> VirtualRouter<T> {
+    /// Construct a new router.
+    pub const fn new() -> Self {
+        Self {
+            hosts: HostRouter::new(),
+            any: Router::new(),
+        }
+    }
+
+    /// Insert a route into the router.
+    ///
+    /// Routes starting with `/` match any host, while other routes are split into a host
+    /// and a path at the first `/`.
+    ///
+    /// ```rust
+    /// # use matchit::VirtualRouter;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut router = VirtualRouter::new();
+    /// router.insert("www.example.com/", "Home")?;
+    /// router.insert("*sub.example.com/", "Subdomain")?;
+    /// router.insert("/about", "About")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn insert(&mut self, route: impl Into<String>, value: T) -> Result<(), InsertError> {
+        let mut route = route.into();
+
+        let slash = route.find('/').ok_or(InsertError::InvalidRoute)?;
+        if slash == 0 {
+            return self.any.insert(route, value);
+        }
+
+        let path = route.split_off(slash);
+        let host = route;
+
+        let map_conflict = |err| match err {
+            // report the full conflicting route
+            InsertError::Conflict { with } => InsertError::Conflict {
+                with: format!("{}{}", host, with),
+            },
+            err => err,
+        };
+
+        if let Some(router) = self.hosts.route_mut(&host) {
+            return router.insert(path, value).map_err(map_conflict);
+        }
+
+        // validate the path before the host is inserted
+        let mut router = Router::new();
+        router.insert(path, value)?;
+        self.hosts.insert(host.clone(), router)
+    }
+
+    /// Tries to find a value in the router matching the given host and path.
+    ///
+    /// If neither the routes of the host nor the routes without a host match the path, a
+    /// trailing slash recommendation for either is returned.
+    pub fn at<'m, 'p>(
+        &'m self,
+        host: &'p str,
+        path: &'p str,
+    ) -> Result<Match<'m, 'p, &'m T>, MatchError> {
+        let (value, params) = self.find(host, path)?;
+
+        Ok(Match {
+            // SAFETY: We only expose &mut T through &mut self
+            value: unsafe { &*value },
+            params,
+        })
+    }
+
+    /// Tries to find a value in the router matching the given host and path,
+    /// returning a mutable reference.
+    pub fn at_mut<'m, 'p>(
+        &'m mut self,
+        host: &'p str,
+        path: &'p str,
+    ) -> Result<Match<'m, 'p, &'m mut T>, MatchError> {
+        let (value, params) = self.find(host, path)?;
+
+        Ok(Match {
+            // SAFETY: We have &mut self
+            value: unsafe { &mut *value },
+            params,
+        })
+    }
+
+    fn find<'m, 'p>(
+        &'m self,
+        host: &'p str,
+        path: &'p str,
+    ) -> Result<(*mut T, Params<'m, 'p>), MatchError> {
+        let mut tsr = None;
+
+        // try the routes of every matching host, in priority order
+        let matched = self.hosts.find_map(host, |router, mut params| {
+            // SAFETY: The router is only read, values are written to through their own cells
+            let router = unsafe { &*router };
+
+            match router.find(path.as_bytes()) {
+                Ok((value, path_params)) => {
+                    for (key, value) in path_params.iter() {
+                        params.push(key.as_bytes(), value.as_bytes());
+                    }
+
+                    Some((value.value.get(), params))
+                }
+                Err(MatchError::NotFound) => None,
+                Err(err) => {
+                    tsr.get_or_insert(err);
+                    None
+                }
+            }
+        });
+
+        if let Some(matched) = matched {
+            return Ok(matched);
+        }
+
+        match self.any.find(path.as_bytes()) {
+            Ok((value, params)) => Ok((value.value.get(), params)),
+            Err(MatchError::NotFound) => Err(tsr.unwrap_or(MatchError::NotFound)),
+            Err(err) => Err(err),
+        }
+    }
+}
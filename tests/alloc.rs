@@ -0,0 +1,94 @@
+use matchit::{MatchScratch, Router};
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+// Counts the allocations made by the current thread.
+struct Counting;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+fn allocations(f: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.with(Cell::get);
+    f();
+    ALLOCATIONS.with(Cell::get) - before
+}
+
+#[test]
+fn at_with_does_not_allocate() {
+    let mut router = Router::new();
+    for route in [
+        "/",
+        "/users/:id",
+        "/users/:id/:post",
+        "/users/new",
+        "/repos/:owner/:repo/pulls/:number/comments/:comment",
+        "/files/*path",
+        "/:a/:b/:c/:d/:e/:f",
+        "/x/y/z",
+    ] {
+        router.insert(route, route).unwrap();
+    }
+
+    let paths = [
+        ("/", 0),
+        ("/users/1", 1),
+        ("/users/new", 0),
+        ("/users/1/2", 2),
+        ("/repos/rust-lang/rust/pulls/1/comments/2", 4),
+        ("/files/a/b/c", 1),
+        // backtracks from the static `/x/y/z` route
+        ("/x/y/c/d/e/f", 6),
+    ];
+
+    let frozen = router.clone().freeze();
+    let mut scratch = MatchScratch::new();
+    let mut frozen_scratch = MatchScratch::new();
+
+    // grow the buffers first
+    for (path, _) in paths {
+        router.at_with(&mut scratch, path).unwrap();
+        frozen.at_with(&mut frozen_scratch, path).unwrap();
+    }
+
+    for (path, params) in paths {
+        let expected = *router.at(path).unwrap().value;
+        let count = allocations(|| {
+            let matched = router.at_with(&mut scratch, path).unwrap();
+            assert_eq!(*matched.value, expected);
+            assert_eq!(matched.params.len(), params);
+        });
+        assert_eq!(count, 0, "matching '{}' allocated", path);
+
+        let count = allocations(|| {
+            frozen.at_with(&mut frozen_scratch, path).unwrap();
+        });
+        assert_eq!(
+            count, 0,
+            "matching '{}' with a frozen router allocated",
+            path
+        );
+    }
+
+    // failed matches don't allocate either
+    let count = allocations(|| {
+        assert!(router.at_with(&mut scratch, "/users/1/2/3").is_err());
+    });
+    assert_eq!(count, 0);
+}
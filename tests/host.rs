@@ -0,0 +1,193 @@
+use matchit::{HostRouter, InsertError, MatchError};
+
+fn router() -> HostRouter<&'static str> {
+    let mut router = HostRouter::new();
+
+    for route in [
+        "example.com",
+        "www.example.com",
+        "api.example.com",
+        ":tenant.example.com",
+        "admin.:tenant.example.com",
+        "*sub.cdn.example.com",
+        "img.cdn.example.com",
+        ":region.:tenant.example.net",
+    ] {
+        router
+            .insert(route, route)
+            .unwrap_or_else(|e| panic!("error when inserting route '{}': {:?}", route, e));
+    }
+
+    router
+}
+
+#[test]
+fn static_hosts() {
+    let router = router();
+
+    for host in ["example.com", "www.example.com", "api.example.com"] {
+        let matched = router.at(host).unwrap();
+        assert_eq!(*matched.value, host);
+        assert!(matched.params.is_empty());
+    }
+
+    assert_eq!(router.at("example.org").err(), Some(MatchError::NotFound));
+    assert_eq!(router.at("com").err(), Some(MatchError::NotFound));
+    assert_eq!(router.at("").err(), Some(MatchError::NotFound));
+}
+
+#[test]
+fn params() {
+    let router = router();
+
+    let matched = router.at("acme.example.com").unwrap();
+    assert_eq!(*matched.value, ":tenant.example.com");
+    assert_eq!(matched.params.get("tenant"), Some("acme"));
+
+    let matched = router.at("admin.acme.example.com").unwrap();
+    assert_eq!(*matched.value, "admin.:tenant.example.com");
+    assert_eq!(matched.params.get("tenant"), Some("acme"));
+
+    let matched = router.at("eu-west.acme.example.net").unwrap();
+    let params = matched.params.iter().collect::<Vec<_>>();
+    assert_eq!(params, [("tenant", "acme"), ("region", "eu-west")]);
+
+    // parameters match a single label
+    assert_eq!(
+        router.at("a.b.example.com").err(),
+        Some(MatchError::NotFound)
+    );
+}
+
+#[test]
+fn catch_all() {
+    let router = router();
+
+    let matched = router.at("static.cdn.example.com").unwrap();
+    assert_eq!(*matched.value, "*sub.cdn.example.com");
+    assert_eq!(matched.params.get("sub"), Some("static"));
+
+    let matched = router.at("a.bb.ccc.cdn.example.com").unwrap();
+    assert_eq!(matched.params.get("sub"), Some("a.bb.ccc"));
+
+    // static labels have priority
+    assert_eq!(
+        router.at("img.cdn.example.com").map(|m| *m.value),
+        Ok("img.cdn.example.com")
+    );
+
+    // catch-alls match at least one label
+    assert_eq!(
+        router.at("cdn.example.com").map(|m| *m.value),
+        Ok(":tenant.example.com")
+    );
+}
+
+#[test]
+fn normalization() {
+    let router = router();
+
+    assert_eq!(
+        router.at("WWW.Example.COM").map(|m| *m.value),
+        Ok("www.example.com")
+    );
+    assert_eq!(
+        router.at("www.example.com.").map(|m| *m.value),
+        Ok("www.example.com")
+    );
+
+    // parameters keep the case of the host
+    let matched = router.at("ACME.example.com.").unwrap();
+    assert_eq!(matched.params.get("tenant"), Some("ACME"));
+
+    let mut router = HostRouter::new();
+    router.insert("API.:Tenant.Example.com", ()).unwrap();
+    let matched = router.at("api.x.example.com").unwrap();
+    assert_eq!(matched.params.get("Tenant"), Some("x"));
+}
+
+#[test]
+fn invalid_routes() {
+    let mut router = router();
+
+    for (route, err) in [
+        ("", InsertError::InvalidRoute),
+        ("example..com", InsertError::InvalidRoute),
+        (".example.com", InsertError::InvalidRoute),
+        ("exa mple.com", InsertError::InvalidRoute),
+        ("example.com/path", InsertError::InvalidRoute),
+        ("a.*sub.example.com", InsertError::InvalidCatchAll),
+        ("x*sub.example.com", InsertError::InvalidCatchAll),
+        ("*host", InsertError::InvalidCatchAll),
+        (":a:b.example.com", InsertError::TooManySegmentParams),
+        (":.example.com", InsertError::UnnamedParam),
+        ("a:b.example.com", InsertError::InvalidRoute),
+        ("shop-:id.example.org", InsertError::InvalidRoute),
+        (":a*b.example.com", InsertError::TooManySegmentParams),
+        ("*", InsertError::InvalidCatchAll),
+    ] {
+        assert_eq!(router.insert(route, ""), Err(err), "{}", route);
+    }
+
+    // conflicts are reported in the original order
+    assert_eq!(
+        router.insert(":name.example.com", ""),
+        Err(InsertError::Conflict {
+            with: ":tenant.example.com".to_owned()
+        })
+    );
+}
+
+#[test]
+fn at_mut() {
+    let mut router = HostRouter::new();
+    router.insert(":tenant.example.com", 0).unwrap();
+
+    *router.at_mut("acme.example.com").unwrap().value += 1;
+    assert_eq!(router.at("other.example.com").map(|m| *m.value), Ok(1));
+}
+
+#[test]
+fn catch_all_first() {
+    let mut router = HostRouter::new();
+    router
+        .insert("*sub.example.com", "*sub.example.com")
+        .unwrap();
+    router.insert("example.com", "example.com").unwrap();
+
+    let matched = router.at("a.b.example.com").unwrap();
+    assert_eq!(matched.params.get("sub"), Some("a.b"));
+    assert_eq!(
+        router.at("example.com").map(|m| *m.value),
+        Ok("example.com")
+    );
+}
+
+#[test]
+fn unnamed_catch_all() {
+    let mut router = HostRouter::new();
+    router.insert("*.cdn.example.com", "cdn").unwrap();
+    router.insert("img.cdn.example.com", "img").unwrap();
+
+    let matched = router.at("a.b.cdn.example.com").unwrap();
+    assert_eq!(*matched.value, "cdn");
+    assert!(matched.params.is_empty());
+    assert_eq!(
+        router.at("img.cdn.example.com").map(|m| *m.value),
+        Ok("img")
+    );
+
+    // conflicts are reported as the route was written
+    assert_eq!(
+        router.insert("*sub.cdn.example.com", ""),
+        Err(InsertError::Conflict {
+            with: "*.cdn.example.com".to_owned()
+        })
+    );
+    assert_eq!(
+        router.insert("*.cdn.example.com", ""),
+        Err(InsertError::Conflict {
+            with: "*.cdn.example.com".to_owned()
+        })
+    );
+}
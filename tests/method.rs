@@ -0,0 +1,198 @@
+use matchit::{InsertError, MatchError, MethodError, MethodRouter};
+
+fn router() -> MethodRouter<&'static str> {
+    let mut router = MethodRouter::new();
+
+    for (method, route) in [
+        ("GET", "/"),
+        ("GET", "/users/:id"),
+        ("PUT", "/users/:id"),
+        ("GET", "/users/:id/posts"),
+        ("POST", "/users/new"),
+        ("GET", "/files/*path"),
+        ("HEAD", "/files/*path"),
+        ("DELETE", "/files/*path"),
+        ("OPTIONS", "/files/*path"),
+        ("POST", "/*rest"),
+    ] {
+        router
+            .insert(method, route, route)
+            .unwrap_or_else(|e| panic!("error when inserting route '{}': {:?}", route, e));
+    }
+
+    router
+}
+
+#[test]
+fn methods() {
+    let router = router();
+
+    let matched = router.at("PUT", "/users/1").unwrap();
+    assert_eq!(*matched.value, "/users/:id");
+    assert_eq!(matched.params.get("id"), Some("1"));
+
+    assert_eq!(
+        router.at("GET", "/users/1/posts").map(|m| *m.value),
+        Ok("/users/:id/posts")
+    );
+    assert_eq!(
+        router.at("POST", "/users/new").map(|m| *m.value),
+        Ok("/users/new")
+    );
+    assert_eq!(
+        router.at("DELETE", "/files/a/b").map(|m| *m.value),
+        Ok("/files/*path")
+    );
+    assert_eq!(router.at("POST", "/other").map(|m| *m.value), Ok("/*rest"));
+
+    // methods are case-sensitive
+    assert_eq!(
+        router.at("get", "/").err(),
+        Some(MethodError::NotAllowed {
+            allow: "GET, HEAD, OPTIONS"
+        })
+    );
+}
+
+#[test]
+fn not_found() {
+    let router = router();
+
+    assert_eq!(
+        router.at("GET", "/users/1/posts/").err(),
+        Some(MethodError::NotFound(MatchError::ExtraTrailingSlash))
+    );
+
+    let mut router = MethodRouter::new();
+    router.insert("GET", "/home", ()).unwrap();
+    assert_eq!(
+        router.at("GET", "/away").err(),
+        Some(MethodError::NotFound(MatchError::NotFound))
+    );
+}
+
+#[test]
+fn not_allowed() {
+    let router = router();
+
+    // a route matches, even though another route would match the method
+    assert_eq!(
+        router.at("POST", "/users/1").err(),
+        Some(MethodError::NotAllowed {
+            allow: "GET, HEAD, PUT, OPTIONS"
+        })
+    );
+
+    assert_eq!(
+        router.at("GET", "/users/new").err(),
+        Some(MethodError::NotAllowed {
+            allow: "POST, OPTIONS"
+        })
+    );
+
+    assert_eq!(
+        router.at("PATCH", "/files/a").err(),
+        Some(MethodError::NotAllowed {
+            allow: "GET, HEAD, DELETE, OPTIONS"
+        })
+    );
+}
+
+#[test]
+fn head_and_options() {
+    let router = router();
+
+    // `HEAD` falls back to `GET`
+    assert_eq!(
+        router.at("HEAD", "/users/1").map(|m| *m.value),
+        Ok("/users/:id")
+    );
+    assert_eq!(
+        router.at("HEAD", "/users/new").err(),
+        Some(MethodError::NotAllowed {
+            allow: "POST, OPTIONS"
+        })
+    );
+
+    assert_eq!(
+        router.at("OPTIONS", "/users/1").err(),
+        Some(MethodError::Options {
+            allow: "GET, HEAD, PUT, OPTIONS"
+        })
+    );
+
+    // explicit values take precedence
+    assert_eq!(
+        router.at("HEAD", "/files/a").map(|m| *m.value),
+        Ok("/files/*path")
+    );
+    assert_eq!(
+        router.at("OPTIONS", "/files/a").map(|m| *m.value),
+        Ok("/files/*path")
+    );
+}
+
+#[test]
+fn conflicts() {
+    let mut router = router();
+
+    assert_eq!(
+        router.insert("GET", "/users/:id", ""),
+        Err(InsertError::Conflict {
+            with: "/users/:id".to_owned()
+        })
+    );
+
+    assert_eq!(
+        router.insert("PATCH", "/users/:user_id", ""),
+        Err(InsertError::Conflict {
+            with: "/users/:id".to_owned()
+        })
+    );
+
+    // a route that matches an existing route with different parameter names
+    let mut router = MethodRouter::new();
+    router.insert("GET", "/:a", "/:a").unwrap();
+    assert_eq!(
+        router.insert("PUT", "/:b", "/:b"),
+        Err(InsertError::Conflict {
+            with: "/:a".to_owned()
+        })
+    );
+    router.insert("PUT", "/:a", "/:a").unwrap();
+    assert_eq!(router.at("PUT", "/x").map(|m| *m.value), Ok("/:a"));
+
+    // a catch-all matches the text of another route, but is a different route
+    let mut router = MethodRouter::new();
+    router.insert("GET", "/*id", 1).unwrap();
+    assert_eq!(
+        router.insert("POST", "/:id", 2),
+        Err(InsertError::Conflict {
+            with: "/*id".to_owned()
+        })
+    );
+    assert_eq!(
+        router.at("POST", "/a/b").err(),
+        Some(MethodError::NotAllowed {
+            allow: "GET, HEAD, OPTIONS"
+        })
+    );
+}
+
+#[test]
+fn at_mut() {
+    let mut router = MethodRouter::new();
+    router.insert("GET", "/", 1).unwrap();
+    router.insert("POST", "/", 2).unwrap();
+
+    *router.at_mut("HEAD", "/").unwrap().value += 10;
+    assert_eq!(router.at("GET", "/").map(|m| *m.value), Ok(11));
+    assert_eq!(router.at("POST", "/").map(|m| *m.value), Ok(2));
+
+    assert_eq!(
+        router.at_mut("OPTIONS", "/").err(),
+        Some(MethodError::Options {
+            allow: "GET, HEAD, POST, OPTIONS"
+        })
+    );
+}
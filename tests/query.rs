@@ -0,0 +1,181 @@
+use matchit::{InsertError, MatchError, QueryRouter, Router};
+
+#[test]
+fn at_uri() {
+    let mut router = Router::new();
+    router.insert("/users/:id", "/users/:id").unwrap();
+    router.insert("/about", "/about").unwrap();
+
+    for (uri, route, id) in [
+        ("/users/1", "/users/:id", Some("1")),
+        ("/users/2?fields=name", "/users/:id", Some("2")),
+        ("/users/3#top", "/users/:id", Some("3")),
+        ("/users/4?a=b#c?d", "/users/:id", Some("4")),
+        ("/users/5#c?d", "/users/:id", Some("5")),
+        ("/about?", "/about", None),
+        ("/about#", "/about", None),
+    ] {
+        let matched = router.at_uri(uri).unwrap();
+        assert_eq!(*matched.value, route);
+        assert_eq!(matched.params.get("id"), id);
+    }
+
+    assert_eq!(router.at_uri("?/about").err(), Some(MatchError::NotFound));
+    assert_eq!(
+        router.at_uri("/about/?x").err(),
+        Some(MatchError::ExtraTrailingSlash)
+    );
+}
+
+fn router() -> QueryRouter<&'static str> {
+    let mut router = QueryRouter::new();
+
+    for route in [
+        "/search",
+        "/search?q",
+        "/search?q&page",
+        "/search?tag",
+        "/users/:id",
+        "/users/:id?fields",
+        "/files/*path?download",
+    ] {
+        router
+            .insert(route, route)
+            .unwrap_or_else(|e| panic!("error when inserting route '{}': {:?}", route, e));
+    }
+
+    router
+}
+
+#[test]
+fn query_params() {
+    let router = router();
+
+    for (uri, route, params) in [
+        ("/search", "/search", vec![]),
+        ("/search?", "/search", vec![]),
+        ("/search?other=1", "/search", vec![]),
+        ("/search?q=rust", "/search?q", vec![("q", "rust")]),
+        ("/search?q", "/search?q", vec![("q", "")]),
+        ("/search?q=a&q=b", "/search?q", vec![("q", "a")]),
+        (
+            "/search?page=2&q=rust",
+            "/search?q&page",
+            vec![("q", "rust"), ("page", "2")],
+        ),
+        ("/search?page=2", "/search", vec![]),
+        ("/search?tag=x#q", "/search?tag", vec![("tag", "x")]),
+        ("/search#?q=x", "/search", vec![]),
+        ("/users/1", "/users/:id", vec![("id", "1")]),
+        (
+            "/users/1?fields=a,b",
+            "/users/:id?fields",
+            vec![("id", "1"), ("fields", "a,b")],
+        ),
+        (
+            "/files/a/b?download",
+            "/files/*path?download",
+            vec![("path", "a/b"), ("download", "")],
+        ),
+    ] {
+        let matched = router.at(uri).unwrap();
+        assert_eq!(*matched.value, route, "{}", uri);
+        assert_eq!(matched.params.iter().collect::<Vec<_>>(), params, "{}", uri);
+    }
+
+    // a route requires its query parameters
+    assert_eq!(router.at("/files/a").err(), Some(MatchError::NotFound));
+    assert_eq!(router.at("/missing?q=1").err(), Some(MatchError::NotFound));
+}
+
+#[test]
+fn fallback() {
+    let mut router = QueryRouter::new();
+    router.insert("/s/new?q", "new").unwrap();
+    router.insert("/s/:x", "x").unwrap();
+    router.insert("/*rest?all", "rest").unwrap();
+
+    assert_eq!(router.at("/s/new?q").map(|m| *m.value), Ok("new"));
+
+    // a route without its query parameters falls back to the next matching route
+    let matched = router.at("/s/new").unwrap();
+    assert_eq!(*matched.value, "x");
+    assert_eq!(matched.params.iter().collect::<Vec<_>>(), [("x", "new")]);
+
+    let matched = router.at("/s/a/b?all=1").unwrap();
+    assert_eq!(*matched.value, "rest");
+    assert_eq!(
+        matched.params.iter().collect::<Vec<_>>(),
+        [("rest", "s/a/b"), ("all", "1")]
+    );
+
+    assert_eq!(router.at("/s/a/b").err(), Some(MatchError::NotFound));
+    assert_eq!(
+        router.at("/s/new/").err(),
+        Some(MatchError::ExtraTrailingSlash)
+    );
+}
+
+#[test]
+fn invalid_routes() {
+    let mut router = router();
+
+    for route in [
+        "/a?", "/a?b&", "/a?b&&c", "/a?b=1", "/a?b?c", "/a?b&b", "/a b?c",
+    ] {
+        assert_eq!(
+            router.insert(route, ""),
+            Err(InsertError::InvalidRoute),
+            "{}",
+            route
+        );
+    }
+}
+
+#[test]
+fn conflicts() {
+    let mut router = router();
+
+    assert_eq!(
+        router.insert("/search", ""),
+        Err(InsertError::Conflict {
+            with: "/search".to_owned()
+        })
+    );
+    assert_eq!(
+        router.insert("/search?page&q", ""),
+        Err(InsertError::Conflict {
+            with: "/search?q&page".to_owned()
+        })
+    );
+    assert_eq!(
+        router.insert("/users/:user_id?fields", ""),
+        Err(InsertError::Conflict {
+            with: "/users/:id".to_owned()
+        })
+    );
+
+    router.insert("/search?page", "").unwrap();
+
+    // a catch-all matches the text of another route, but is a different route
+    let mut router = QueryRouter::new();
+    router.insert("/f/*p", 1).unwrap();
+    assert_eq!(
+        router.insert("/f/:p?x", 2),
+        Err(InsertError::Conflict {
+            with: "/f/*p".to_owned()
+        })
+    );
+    assert_eq!(router.at("/f/a/b?x").map(|m| *m.value), Ok(1));
+}
+
+#[test]
+fn at_mut() {
+    let mut router = QueryRouter::new();
+    router.insert("/", 1).unwrap();
+    router.insert("/?a", 2).unwrap();
+
+    *router.at_mut("/?a=1").unwrap().value += 10;
+    assert_eq!(router.at("/?a").map(|m| *m.value), Ok(12));
+    assert_eq!(router.at("/").map(|m| *m.value), Ok(1));
+}
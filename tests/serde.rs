@@ -0,0 +1,234 @@
+use matchit::{ArchivedRouter, MatchError, Router};
+use serde_json::{json, Value};
+
+fn router() -> Router<String> {
+    let mut router = Router::new();
+
+    for route in [
+        "/",
+        "/cmd/:tool/",
+        "/cmd/:tool/:sub",
+        "/cmd/whoami",
+        "/src/*filepath",
+        "/search/",
+        "/search/:query",
+        "/user_:name",
+        "/user_:name/about",
+        "/files/:dir/*filepath",
+        "/doc/go1.html",
+        "/info/:user/public",
+        "/info/:user/project/:project",
+        "/ünìcodé/:id",
+    ] {
+        router.insert(route, route.to_owned()).unwrap();
+    }
+
+    router
+}
+
+#[test]
+fn round_trip() {
+    let router = router();
+    let json = serde_json::to_value(&router).unwrap();
+    let loaded: Router<String> = serde_json::from_value(json.clone()).unwrap();
+
+    assert_eq!(serde_json::to_value(&loaded).unwrap(), json);
+    assert_eq!(loaded.check_priorities(), router.check_priorities());
+
+    for path in [
+        "/",
+        "/cmd/test/",
+        "/cmd/test/3",
+        "/cmd/whoami",
+        "/cmd/whoami/",
+        "/src/some/file.png",
+        "/search/someth!ng+in+ünìcodé",
+        "/user_gopher/about",
+        "/files/js/inc/framework.js",
+        "/doc/go1.html",
+        "/info/gordon/project/go",
+        "/ünìcodé/1",
+        "/nope",
+    ] {
+        let expected = router.at(path).map(|m| (m.value.clone(), m.params.clone()));
+        let found = loaded.at(path).map(|m| (m.value.clone(), m.params.clone()));
+        assert_eq!(found, expected, "loaded router differs for '{}'", path);
+    }
+}
+
+#[test]
+fn round_trip_empty() {
+    let router = Router::<u32>::new();
+    let loaded: Router<u32> =
+        serde_json::from_value(serde_json::to_value(&router).unwrap()).unwrap();
+    assert_eq!(loaded.at("/").err(), Some(MatchError::NotFound));
+
+    let mut router = Router::new();
+    router.insert("/home", 1).unwrap();
+    router.retain(|_| false);
+    let mut loaded: Router<u32> =
+        serde_json::from_value(serde_json::to_value(&router).unwrap()).unwrap();
+
+    loaded.insert("/home", 2).unwrap();
+    assert_eq!(loaded.at("/home").map(|m| *m.value), Ok(2));
+}
+
+#[test]
+fn static_routes_are_indexed() {
+    let router = router();
+    let mut loaded: Router<String> =
+        serde_json::from_value(serde_json::to_value(&router).unwrap()).unwrap();
+
+    *loaded.at_mut("/doc/go1.html").unwrap().value = "changed".to_owned();
+    assert_eq!(loaded.at("/doc/go1.html").unwrap().value, "changed");
+}
+
+// Applies `f` to the node at the given child indices from the root.
+fn corrupt(path: &[usize], f: impl FnOnce(&mut Value)) -> Result<Router<u32>, String> {
+    let mut router = Router::new();
+    router.insert("/users/:id", 1).unwrap();
+    router.insert("/users/:id/posts", 2).unwrap();
+    router.insert("/users/new", 3).unwrap();
+    router.insert("/files/*path", 4).unwrap();
+
+    let mut json = serde_json::to_value(&router).unwrap();
+    let mut node = &mut json["root"];
+    for &i in path {
+        node = &mut node["children"][i];
+    }
+
+    f(node);
+    serde_json::from_value(json).map_err(|e| e.to_string())
+}
+
+#[test]
+fn invalid_trees() {
+    // sanity check
+    let router = corrupt(&[], |_| {}).unwrap();
+    assert_eq!(router.at("/users/1/posts").map(|m| *m.value), Ok(2));
+
+    type Case = (&'static [usize], fn(&mut Value), &'static str);
+
+    let cases: [Case; 10] = [
+        (&[], |node| node["priority"] = json!(5), "priority"),
+        (&[], |node| node["node_type"] = json!("Static"), "root"),
+        (&[], |node| node["indices"] = json!([b'f']), "indices"),
+        (&[], |node| node["indices"] = json!([b'x', b'f']), "indices"),
+        (&[0], |node| node["wild_child"] = json!(false), "wildcard"),
+        (&[0, 1], |node| node["prefix"] = json!([b':']), "parameter"),
+        (
+            &[0, 1],
+            |node| node["param_remapping"] = json!([]),
+            "remapping",
+        ),
+        (
+            &[0, 1],
+            |node| node["param_remapping"] = json!([[b'i', b'd']]),
+            "parameter names",
+        ),
+        (&[1, 0], |node| node["value"] = json!(null), "catch-all"),
+        (&[1, 0], |node| node["priority"] = json!(2), "priority"),
+    ];
+
+    for (path, f, expected) in cases {
+        let err = corrupt(path, f).err().unwrap();
+        assert!(err.starts_with("invalid router"), "{}", err);
+        assert!(
+            err.contains(expected),
+            "expected '{}', found '{}'",
+            expected,
+            err
+        );
+    }
+
+    // routes must be valid UTF-8
+    let err = corrupt(&[1], |node| node["prefix"] = json!([b'f', 0xff, b'/']))
+        .err()
+        .unwrap();
+    assert!(err.contains("UTF-8"), "{}", err);
+}
+
+// Archives a small router and replaces every occurrence of `from` in the archive with `to`.
+fn corrupt_archive(from: &[u8], to: &[u8]) -> Result<(), String> {
+    let mut router = Router::new();
+    router.insert("/users/:id", 1).unwrap();
+    router.insert("/users/:id/posts", 2).unwrap();
+    router.insert("/users/new", 3).unwrap();
+    router.insert("/files/*path", 4).unwrap();
+
+    let mut bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&router.into_archivable()).unwrap();
+    for i in 0..=bytes.len() - from.len() {
+        if bytes[i..].starts_with(from) {
+            bytes[i..i + to.len()].copy_from_slice(to);
+        }
+    }
+
+    rkyv::access::<ArchivedRouter<i32>, rkyv::rancor::Error>(&bytes)
+        .map(|archived| {
+            assert_eq!(
+                archived.at("/users/1/posts").map(|m| m.value.to_native()),
+                Ok(2)
+            )
+        })
+        .map_err(|e| e.to_string())
+}
+
+#[test]
+fn invalid_archives() {
+    // sanity check
+    corrupt_archive(b"", b"").unwrap();
+
+    let cases: [(&[u8], &[u8], &str); 4] = [
+        (b":id", b"xid", "parameter"),
+        (b"*path", b"*pa/h", "catch-all"),
+        (b"files", b"fi\xffes", "UTF-8"),
+        (b"ne", b"ze", "indices"),
+    ];
+
+    for (from, to, expected) in cases {
+        let err = corrupt_archive(from, to).err().unwrap();
+        assert!(err.contains("invalid router"), "{}", err);
+        assert!(
+            err.contains(expected),
+            "expected '{}', found '{}'",
+            expected,
+            err
+        );
+    }
+}
+
+#[test]
+fn deep_trees() {
+    const DEPTH: u32 = 256;
+
+    // every route is nested in the previous one
+    let mut router = Router::new();
+    let mut route = String::from("/");
+    for i in 0..DEPTH {
+        route.push('a');
+        router.insert(route.clone(), i).unwrap();
+    }
+
+    let json = serde_json::to_value(&router).unwrap();
+    let loaded: Router<u32> = serde_json::from_value(json.clone()).unwrap();
+    assert_eq!(loaded.at(&route).map(|m| *m.value), Ok(DEPTH - 1));
+
+    // the deepest node is checked as well
+    let mut corrupted = json;
+    let mut node = &mut corrupted["root"];
+    while !node["children"][0].is_null() {
+        node = &mut node["children"][0];
+    }
+    node["prefix"] = json!([b'a', 0xff]);
+    let err = serde_json::from_value::<Router<u32>>(corrupted)
+        .err()
+        .unwrap();
+    assert!(err.to_string().contains("UTF-8"), "{}", err);
+
+    let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&router.into_archivable()).unwrap();
+    let archived = rkyv::access::<ArchivedRouter<u32>, rkyv::rancor::Error>(&bytes).unwrap();
+    assert_eq!(
+        archived.at(&route).map(|m| m.value.to_native()),
+        Ok(DEPTH - 1)
+    );
+}
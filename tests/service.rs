@@ -0,0 +1,152 @@
+use std::convert::Infallible;
+use std::future::{ready, Ready};
+use std::task::{Context, Poll};
+
+use http::Request;
+use matchit::{MatchError, OwnedParams, Router, RouterLayer, RouterService};
+use tower::{service_fn, Layer, Service, ServiceExt};
+
+// Responds with its name, the parameters and the match error of the request.
+#[derive(Clone)]
+struct Named(&'static str);
+
+type Response = (&'static str, Option<OwnedParams>, Option<MatchError>);
+
+impl<B> Service<Request<B>> for Named {
+    type Response = Response;
+    type Error = Infallible;
+    type Future = Ready<Result<Response, Infallible>>;
+
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: Request<B>) -> Self::Future {
+        let params = req.extensions().get::<OwnedParams>().cloned();
+        let err = req.extensions().get::<MatchError>().copied();
+        ready(Ok((self.0, params, err)))
+    }
+}
+
+fn router() -> Router<Named> {
+    let mut router = Router::new();
+    router.insert("/", Named("index")).unwrap();
+    router.insert("/users/:id", Named("user")).unwrap();
+    router.insert("/files/*path", Named("files")).unwrap();
+    router.insert("/blog/", Named("blog")).unwrap();
+    router
+}
+
+async fn call<S>(service: &S, path: &str) -> Response
+where
+    S: Service<Request<()>, Response = Response, Error = Infallible> + Clone,
+{
+    let request = Request::get(path).body(()).unwrap();
+    service.clone().oneshot(request).await.unwrap()
+}
+
+#[tokio::test]
+async fn routes() {
+    let service = RouterService::new(router(), Named("fallback"));
+
+    let (name, params, err) = call(&service, "/users/1").await;
+    assert_eq!(name, "user");
+    assert_eq!(params.unwrap().get("id"), Some("1"));
+    assert_eq!(err, None);
+
+    let (name, params, _) = call(&service, "/files/a/b.css?query").await;
+    assert_eq!(name, "files");
+    assert_eq!(params.unwrap().get("path"), Some("a/b.css"));
+
+    let (name, params, _) = call(&service, "/").await;
+    assert_eq!(name, "index");
+    assert!(params.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn fallbacks() {
+    let service = RouterService::new(router(), Named("fallback"));
+    assert_eq!(
+        call(&service, "/nope").await,
+        ("fallback", None, Some(MatchError::NotFound))
+    );
+    assert_eq!(
+        call(&service, "/blog").await,
+        ("fallback", None, Some(MatchError::MissingTrailingSlash))
+    );
+
+    let service = service.trailing_slash_fallback(Named("redirect"));
+    assert_eq!(
+        call(&service, "/nope").await,
+        ("fallback", None, Some(MatchError::NotFound))
+    );
+    assert_eq!(
+        call(&service, "/blog").await,
+        ("redirect", None, Some(MatchError::MissingTrailingSlash))
+    );
+    assert_eq!(
+        call(&service, "/users/1/").await,
+        ("redirect", None, Some(MatchError::ExtraTrailingSlash))
+    );
+}
+
+#[tokio::test]
+async fn trailing_slash_fallback_type() {
+    // the trailing slash fallback has its own type, and a future that is not `Unpin`
+    let redirect = service_fn(|req: Request<()>| async move {
+        let err = req.extensions().get::<MatchError>().copied();
+        Ok::<Response, Infallible>(("redirect", None, err))
+    });
+
+    let service = RouterService::new(router(), Named("fallback")).trailing_slash_fallback(redirect);
+    assert_eq!(
+        call(&service, "/blog").await,
+        ("redirect", None, Some(MatchError::MissingTrailingSlash))
+    );
+    assert_eq!(call(&service, "/nope").await.0, "fallback");
+    assert_eq!(call(&service, "/users/1").await.0, "user");
+}
+
+#[tokio::test]
+async fn layer() {
+    let service = RouterLayer::new(router()).layer(Named("inner"));
+    assert_eq!(call(&service, "/users/1").await.0, "user");
+    assert_eq!(call(&service, "/nope").await.0, "inner");
+}
+
+// A service that is only ready after being polled once.
+#[derive(Clone)]
+struct Slow {
+    polled: bool,
+}
+
+impl Service<Request<()>> for Slow {
+    type Response = Response;
+    type Error = Infallible;
+    type Future = Ready<Result<Response, Infallible>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+        if self.polled {
+            return Poll::Ready(Ok(()));
+        }
+
+        self.polled = true;
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+
+    fn call(&mut self, _: Request<()>) -> Self::Future {
+        assert!(self.polled, "called before ready");
+        ready(Ok(("slow", None, None)))
+    }
+}
+
+#[tokio::test]
+async fn readiness() {
+    let mut router = Router::new();
+    router.insert("/", Slow { polled: false }).unwrap();
+
+    let service = RouterService::new(router, Slow { polled: false });
+    assert_eq!(call(&service, "/").await.0, "slow");
+    assert_eq!(call(&service, "/nope").await.0, "slow");
+}
@@ -0,0 +1,108 @@
+use matchit::{InsertError, MatchError, Path, Router, Syntax};
+
+// gRPC-style method names like `pkg.Service.Method`
+struct Dotted;
+
+impl Syntax for Dotted {
+    const SEPARATOR: u8 = b'.';
+    const PARAM: u8 = b'{';
+    const CATCH_ALL: u8 = b'#';
+}
+
+fn router() -> Router<&'static str, Dotted> {
+    let mut router = Router::<_, Dotted>::with_syntax();
+
+    for route in [
+        "users.list",
+        "users.{id",
+        "users.{id.posts",
+        "users.{id.posts.",
+        "orders.{id.items.{item",
+        "files.#path",
+    ] {
+        router
+            .insert(route, route)
+            .unwrap_or_else(|e| panic!("error when inserting route '{}': {:?}", route, e));
+    }
+
+    router
+}
+
+#[test]
+fn params() {
+    let router = router();
+
+    for (key, route, params) in [
+        ("users.list", "users.list", vec![]),
+        ("users.1", "users.{id", vec![("id", "1")]),
+        ("users.1.posts", "users.{id.posts", vec![("id", "1")]),
+        ("users.a/b.posts.", "users.{id.posts.", vec![("id", "a/b")]),
+        (
+            "orders.7.items.9",
+            "orders.{id.items.{item",
+            vec![("id", "7"), ("item", "9")],
+        ),
+        ("files.a.b.c", "files.#path", vec![("path", "a.b.c")]),
+    ] {
+        let matched = router.at(key).unwrap();
+        assert_eq!(*matched.value, route, "{}", key);
+        assert_eq!(matched.params.iter().collect::<Vec<_>>(), params, "{}", key);
+    }
+
+    assert_eq!(router.at("users").err(), Some(MatchError::NotFound));
+    assert_eq!(router.at("other.key").err(), Some(MatchError::NotFound));
+}
+
+#[test]
+fn trailing_separator() {
+    let mut router = Router::<_, Dotted>::with_syntax();
+    router.insert("a.{b", ()).unwrap();
+    router.insert("c.d.", ()).unwrap();
+
+    assert_eq!(
+        router.at("a.x.").err(),
+        Some(MatchError::ExtraTrailingSlash)
+    );
+    assert_eq!(
+        router.at("c.d").err(),
+        Some(MatchError::MissingTrailingSlash)
+    );
+    assert_eq!(router.at("a.x/").err(), None);
+}
+
+#[test]
+fn insert_errors() {
+    let mut router = router();
+
+    assert_eq!(
+        router.insert("users.{name", ""),
+        Err(InsertError::Conflict {
+            with: "users.{id".to_owned()
+        })
+    );
+    assert_eq!(router.insert("users.{", ""), Err(InsertError::UnnamedParam));
+    assert_eq!(
+        router.insert("a.#b.c", ""),
+        Err(InsertError::InvalidCatchAll)
+    );
+    assert_eq!(router.insert("#all", ""), Err(InsertError::InvalidCatchAll));
+    assert_eq!(
+        router.insert("a.{b{c", ""),
+        Err(InsertError::TooManySegmentParams)
+    );
+
+    // the sigils of paths have no special meaning
+    router.insert("a.:b.*c", "").unwrap();
+    assert!(router.at("a.x.y").is_err());
+    assert!(router.at("a.:b.*c").is_ok());
+}
+
+#[test]
+fn with_path_syntax() {
+    let mut router = Router::<_, Path>::with_syntax();
+    router.insert("/users/:id", "A User").unwrap();
+    assert_eq!(router.insert("users", ""), Err(InsertError::InvalidRoute));
+
+    let matched = router.at("/users/1").unwrap();
+    assert_eq!(matched.params.get("id"), Some("1"));
+}
@@ -0,0 +1,159 @@
+use matchit::{InsertError, TopicRouter};
+
+fn router() -> TopicRouter<&'static str> {
+    let mut router = TopicRouter::new();
+
+    for filter in [
+        "sport/tennis/player1",
+        "sport/tennis/+",
+        "sport/+/player1",
+        "sport/#",
+        "sport/tennis/#",
+        "+/+",
+        "+",
+        "/+",
+        "+/tennis/#",
+        "a//b",
+        "a/+/b",
+        "$SYS/#",
+        "$SYS/+/uptime",
+    ] {
+        router
+            .insert(filter, filter)
+            .unwrap_or_else(|e| panic!("error when inserting filter '{}': {:?}", filter, e));
+    }
+
+    router
+}
+
+#[test]
+fn matches() {
+    let router = router();
+
+    for (topic, filters) in [
+        (
+            "sport/tennis/player1",
+            vec![
+                "sport/tennis/player1",
+                "sport/tennis/+",
+                "sport/+/player1",
+                "sport/#",
+                "sport/tennis/#",
+                "+/tennis/#",
+            ],
+        ),
+        (
+            "sport/tennis/player2",
+            vec!["sport/tennis/+", "sport/#", "sport/tennis/#", "+/tennis/#"],
+        ),
+        (
+            "sport/tennis",
+            vec!["sport/#", "sport/tennis/#", "+/+", "+/tennis/#"],
+        ),
+        ("sport", vec!["sport/#", "+"]),
+        ("sport/", vec!["sport/#", "+/+"]),
+        (
+            "sport/tennis/",
+            vec!["sport/tennis/+", "sport/#", "sport/tennis/#", "+/tennis/#"],
+        ),
+        ("/finance", vec!["+/+", "/+"]),
+        ("/", vec!["+/+", "/+"]),
+        ("a//b", vec!["a//b", "a/+/b"]),
+        ("a/x/b", vec!["a/+/b"]),
+        ("a/x/y/b", vec![]),
+        ("other", vec!["+"]),
+    ] {
+        assert_eq!(
+            router
+                .matches(topic)
+                .into_iter()
+                .copied()
+                .collect::<Vec<_>>(),
+            filters,
+            "{}",
+            topic
+        );
+    }
+}
+
+#[test]
+fn match_everything() {
+    let mut router = router();
+    router.insert("#", "#").unwrap();
+
+    assert_eq!(router.matches("other"), [&"+", &"#"]);
+    assert_eq!(router.matches("x/y/z"), [&"#"]);
+    assert_eq!(router.matches("/"), [&"+/+", &"/+", &"#"]);
+}
+
+#[test]
+fn reserved_topics() {
+    let mut router = router();
+    router.insert("#", "#").unwrap();
+
+    // wildcards at the first level don't match topics starting with `$`
+    assert_eq!(router.matches("$SYS"), [&"$SYS/#"]);
+    assert_eq!(router.matches("$SYS/broker"), [&"$SYS/#"]);
+    assert_eq!(
+        router.matches("$SYS/broker/uptime"),
+        [&"$SYS/#", &"$SYS/+/uptime"]
+    );
+    assert_eq!(router.matches("$other/tennis"), [] as [&&str; 0]);
+}
+
+#[test]
+fn invalid_topics() {
+    let mut router = router();
+    router.insert("#", "#").unwrap();
+
+    for topic in ["", "sport/+", "sport/#", "+", "a\0b"] {
+        assert!(router.matches(topic).is_empty(), "{}", topic);
+    }
+}
+
+#[test]
+fn invalid_filters() {
+    let mut router = router();
+
+    for (filter, err) in [
+        ("", InsertError::InvalidRoute),
+        ("sport+", InsertError::InvalidRoute),
+        ("sport/+tennis", InsertError::InvalidRoute),
+        ("a\0b", InsertError::InvalidRoute),
+        ("sport#", InsertError::InvalidCatchAll),
+        ("sport/#/ranking", InsertError::InvalidCatchAll),
+        ("sport/tennis#", InsertError::InvalidCatchAll),
+        ("#/x", InsertError::InvalidCatchAll),
+        ("##", InsertError::InvalidCatchAll),
+    ] {
+        assert_eq!(router.insert(filter, ""), Err(err), "{}", filter);
+    }
+
+    let filter = ["+"; 27].join("/");
+    assert_eq!(router.insert(filter, ""), Err(InsertError::TooManyParams));
+
+    // errors don't leave anything behind
+    assert_eq!(router.matches("sport"), [&"sport/#", &"+"]);
+}
+
+#[test]
+fn conflicts() {
+    let mut router = router();
+
+    for filter in ["sport/tennis/+", "sport/#", "+", "a//b"] {
+        assert_eq!(
+            router.insert(filter, ""),
+            Err(InsertError::Conflict {
+                with: filter.to_owned()
+            })
+        );
+    }
+
+    router.insert("#", "").unwrap();
+    assert_eq!(
+        router.insert("#", ""),
+        Err(InsertError::Conflict {
+            with: "#".to_owned()
+        })
+    );
+}
@@ -1,4 +1,4 @@
-use matchit::{InsertError, MatchError, Router};
+use matchit::{ArchivedRouter, InsertError, MatchError, MatchScratch, Router};
 
 #[test]
 fn issue_31() {
@@ -387,6 +387,478 @@ fn issue_12() {
     assert_eq!(matched.params.get("id"), Some("978"));
 }
 
+#[test]
+fn matches() {
+    let mut router = Router::new();
+    router.insert("/users/new", "new").unwrap();
+    router.insert("/users/new/", "new/").unwrap();
+    router.insert("/users/:id", "id").unwrap();
+    router.insert("/users/:id/", "id/").unwrap();
+    router.insert("/users/:id/posts", "posts").unwrap();
+    router.insert("/:object/:id", "object").unwrap();
+    router.insert("/:object/:id/*rest", "rest").unwrap();
+
+    let matches = |path| {
+        router
+            .matches(path)
+            .map(|m| (*m.value, m.params.iter().collect::<Vec<_>>()))
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        matches("/users/new"),
+        [
+            ("new", vec![]),
+            ("id", vec![("id", "new")]),
+            ("object", vec![("object", "users"), ("id", "new")]),
+        ]
+    );
+
+    assert_eq!(
+        matches("/users/new/"),
+        [("new/", vec![]), ("id/", vec![("id", "new")]),]
+    );
+
+    assert_eq!(
+        matches("/users/1/posts"),
+        [
+            ("posts", vec![("id", "1")]),
+            (
+                "rest",
+                vec![("object", "users"), ("id", "1"), ("rest", "posts")]
+            ),
+        ]
+    );
+
+    assert_eq!(matches("/"), []);
+    assert_eq!(matches("/users"), []);
+}
+
+#[test]
+fn at_prefix() {
+    let mut router = Router::new();
+    router.insert("/", "root").unwrap();
+    router.insert("/admin", "admin").unwrap();
+    router.insert("/admin/users/", "users").unwrap();
+    router.insert("/admin/users/:id", "user").unwrap();
+    router.insert("/files/*path", "files").unwrap();
+
+    let at_prefix = |path| {
+        router
+            .at_prefix(path)
+            .map(|(m, rest)| (*m.value, m.params.iter().collect::<Vec<_>>(), rest))
+    };
+
+    assert_eq!(at_prefix("/admin"), Ok(("admin", vec![], "")));
+    assert_eq!(at_prefix("/admin/"), Ok(("admin", vec![], "/")));
+    assert_eq!(at_prefix("/admin/x/y"), Ok(("admin", vec![], "/x/y")));
+    assert_eq!(at_prefix("/admin/users"), Ok(("admin", vec![], "/users")));
+    assert_eq!(at_prefix("/admin/users/"), Ok(("users", vec![], "")));
+    assert_eq!(
+        at_prefix("/admin/users/1/posts/"),
+        Ok(("user", vec![("id", "1")], "/posts/"))
+    );
+    assert_eq!(
+        at_prefix("/files/a/b"),
+        Ok(("files", vec![("path", "a/b")], ""))
+    );
+    assert_eq!(at_prefix("/adminx"), Ok(("root", vec![], "adminx")));
+    assert_eq!(at_prefix("/"), Ok(("root", vec![], "")));
+
+    // prefixes are found after backtracking, and before a trailing slash mismatch
+    let mut router = Router::new();
+    router.insert("/:id", "id").unwrap();
+    router.insert("/:id/posts", "posts").unwrap();
+    router.insert("/users/new", "new").unwrap();
+
+    let at_prefix = |path| {
+        router
+            .at_prefix(path)
+            .map(|(m, rest)| (*m.value, m.params.iter().collect::<Vec<_>>(), rest))
+    };
+
+    assert_eq!(
+        at_prefix("/users/x"),
+        Ok(("id", vec![("id", "users")], "/x"))
+    );
+    assert_eq!(at_prefix("/1/"), Ok(("id", vec![("id", "1")], "/")));
+    assert_eq!(
+        at_prefix("/1/posts/x"),
+        Ok(("posts", vec![("id", "1")], "/x"))
+    );
+
+    let mut router = Router::new();
+    router.insert("/admin", "admin").unwrap();
+    assert_eq!(
+        router.at_prefix("/adminx").map(|(m, _)| *m.value),
+        Err(MatchError::NotFound)
+    );
+    assert_eq!(
+        router.at_prefix("").map(|(m, _)| *m.value),
+        Err(MatchError::NotFound)
+    );
+}
+
+#[test]
+fn at_or_ancestor() {
+    let mut router = Router::new();
+    router.insert("/", "root").unwrap();
+    router.insert("/api", "api").unwrap();
+    router.insert("/api/v2/", "v2").unwrap();
+    router.insert("/api/v2/users", "users").unwrap();
+    router.insert("/tenants/:id", "tenant").unwrap();
+    router.insert("/tenants/:id/billing", "billing").unwrap();
+    router.insert("/tenants/new/x", "new").unwrap();
+
+    let at = |path| {
+        router
+            .at_or_ancestor(path)
+            .map(|(m, rest)| (*m.value, m.params.iter().collect::<Vec<_>>(), rest))
+    };
+
+    assert_eq!(at("/api/v2/users"), Ok(("users", vec![], "")));
+    assert_eq!(at("/api/v2/unknown"), Ok(("v2", vec![], "unknown")));
+    assert_eq!(at("/api/v2/users/1"), Ok(("users", vec![], "/1")));
+    assert_eq!(at("/api/v1"), Ok(("api", vec![], "/v1")));
+    assert_eq!(at("/apix"), Ok(("root", vec![], "apix")));
+    assert_eq!(
+        at("/tenants/1/unknown"),
+        Ok(("tenant", vec![("id", "1")], "/unknown"))
+    );
+    assert_eq!(
+        at("/tenants/new/unknown"),
+        Ok(("tenant", vec![("id", "new")], "/unknown"))
+    );
+
+    // trailing slash redirects are still reported
+    assert_eq!(at("/api/"), Err(MatchError::ExtraTrailingSlash));
+    assert_eq!(at("/api/v2"), Err(MatchError::MissingTrailingSlash));
+
+    let mut router = Router::new();
+    router.insert("/api/users", "users").unwrap();
+    assert_eq!(
+        router.at_or_ancestor("/api/posts").map(|(m, _)| *m.value),
+        Err(MatchError::NotFound)
+    );
+}
+
+#[test]
+fn nest() {
+    let mut billing = Router::new();
+    billing.insert("/", "index").unwrap();
+    billing.insert("/invoices/:invoice", "invoice").unwrap();
+    billing.insert("/invoices/:id/items/:item", "item").unwrap();
+    billing.insert("/files/*path", "files").unwrap();
+
+    let mut router = Router::new();
+    router.insert("/home", "home").unwrap();
+    router.nest("/tenants/:tenant/billing/", billing).unwrap();
+
+    let at = |path| {
+        router
+            .at(path)
+            .map(|m| (*m.value, m.params.iter().collect::<Vec<_>>()))
+    };
+
+    assert_eq!(at("/home"), Ok(("home", vec![])));
+    assert_eq!(
+        at("/tenants/1/billing/"),
+        Ok(("index", vec![("tenant", "1")]))
+    );
+    assert_eq!(
+        at("/tenants/1/billing/invoices/2"),
+        Ok(("invoice", vec![("tenant", "1"), ("invoice", "2")]))
+    );
+    assert_eq!(
+        at("/tenants/1/billing/invoices/2/items/3"),
+        Ok(("item", vec![("tenant", "1"), ("id", "2"), ("item", "3")]))
+    );
+    assert_eq!(
+        at("/tenants/1/billing/files/a/b"),
+        Ok(("files", vec![("tenant", "1"), ("path", "a/b")]))
+    );
+
+    // a failed nest leaves the router unchanged
+    let mut other = Router::new();
+    other.insert("/new", "new").unwrap();
+    other.insert("/", "conflict").unwrap();
+    assert_eq!(
+        router
+            .nest("/tenants/:tenant/billing", other)
+            .unwrap_err()
+            .errors(),
+        [(
+            "/tenants/:tenant/billing/".to_owned(),
+            InsertError::Conflict {
+                with: "/tenants/:tenant/billing/".into()
+            }
+        )]
+    );
+    assert_eq!(
+        router.at("/tenants/1/billing/new").map(|m| *m.value),
+        Err(MatchError::NotFound)
+    );
+    assert_eq!(router.routes().count(), 5);
+
+    if let Err((got, expected)) = router.check_priorities() {
+        panic!("priority mismatch: got '{}', expected '{}'", got, expected)
+    }
+}
+
+#[test]
+fn merge() {
+    let mut a = Router::new();
+    a.insert("/users/:id", "a user").unwrap();
+    a.insert("/files/*path", "a files").unwrap();
+    a.insert("/", "a index").unwrap();
+
+    let mut b = Router::new();
+    b.insert("/", "b index").unwrap();
+    b.insert("/users/:name", "b user").unwrap();
+    b.insert("/users/:id/posts", "b posts").unwrap();
+    b.insert("/files/:file", "b file").unwrap();
+    b.insert("/about", "b about").unwrap();
+
+    let err = a.merge(b).unwrap_err();
+    let mut errors = err.into_errors();
+    errors.sort();
+    assert_eq!(
+        errors,
+        [
+            ("/".to_owned(), InsertError::Conflict { with: "/".into() }),
+            (
+                "/files/:file".to_owned(),
+                InsertError::Conflict {
+                    with: "/files/*path".into()
+                }
+            ),
+            (
+                "/users/:name".to_owned(),
+                InsertError::Conflict {
+                    with: "/users/:id".into()
+                }
+            ),
+        ]
+    );
+
+    // a failed merge leaves the router unchanged
+    assert_eq!(a.at("/").map(|m| *m.value), Ok("a index"));
+    assert_eq!(a.at("/users/1").map(|m| *m.value), Ok("a user"));
+    assert_eq!(
+        a.at("/users/1/posts").map(|m| *m.value),
+        Err(MatchError::NotFound)
+    );
+    assert_eq!(a.at("/about").map(|m| *m.value), Err(MatchError::NotFound));
+    assert_eq!(a.routes().count(), 3);
+
+    // errors name the route being inserted
+    let mut a = Router::new();
+    a.insert("/a/:x", "x").unwrap();
+    let mut b = Router::new();
+    b.insert("/a/:y", "y").unwrap();
+    b.insert("/b", "b").unwrap();
+    assert_eq!(
+        a.merge(b).unwrap_err().errors(),
+        [(
+            "/a/:y".to_owned(),
+            InsertError::Conflict {
+                with: "/a/:x".into()
+            }
+        )]
+    );
+    assert_eq!(a.at("/b").map(|m| *m.value), Err(MatchError::NotFound));
+
+    if let Err((got, expected)) = a.check_priorities() {
+        panic!("priority mismatch: got '{}', expected '{}'", got, expected)
+    }
+}
+
+#[test]
+fn failed_insert() {
+    let mut router = Router::new();
+    router.insert("/a", "a").unwrap();
+    router.insert("/b/:x", "b").unwrap();
+    router.insert("/c/*rest", "c").unwrap();
+
+    let routes =
+        |router: &Router<&str>| router.routes().map(|(route, ..)| route).collect::<Vec<_>>();
+    let before = routes(&router);
+
+    // a failed insertion leaves the tree untouched, including the order of its nodes
+    for _ in 0..3 {
+        assert_eq!(
+            router.insert("/b/:y", "y"),
+            Err(InsertError::Conflict {
+                with: "/b/:x".into()
+            })
+        );
+        assert_eq!(
+            router.insert("/c/*rest", "c"),
+            Err(InsertError::Conflict {
+                with: "/c/*rest".into()
+            })
+        );
+    }
+
+    assert_eq!(routes(&router), before);
+
+    if let Err((got, expected)) = router.check_priorities() {
+        panic!("priority mismatch: got '{}', expected '{}'", got, expected)
+    }
+}
+
+#[test]
+fn map() {
+    let mut router = Router::new();
+    router.insert("/users/:id", "1").unwrap();
+    router.insert("/users/:id/posts", "22").unwrap();
+    router.insert("/*rest", "333").unwrap();
+
+    let mut router = router.map(str::len);
+    assert_eq!(router.at("/users/1").map(|m| *m.value), Ok(1));
+    assert_eq!(router.at("/users/1/posts").map(|m| *m.value), Ok(2));
+    assert_eq!(router.at("/foo").map(|m| *m.value), Ok(3));
+
+    let mut values = router.values_mut().map(|value| *value).collect::<Vec<_>>();
+    values.sort();
+    assert_eq!(values, [1, 2, 3]);
+
+    if let Err((got, expected)) = router.check_priorities() {
+        panic!("priority mismatch: got '{}', expected '{}'", got, expected)
+    }
+
+    let err = router.try_map(|value| if value < 3 { Ok(value) } else { Err(value) });
+    assert_eq!(err.err(), Some(3));
+}
+
+#[test]
+fn retain() {
+    let routes = [
+        "/",
+        "/users/:id",
+        "/users/:id/posts",
+        "/users/new",
+        "/files/*path",
+        "/files/static.json",
+    ];
+
+    let mut router = Router::new();
+    for route in routes {
+        router.insert(route, route).unwrap();
+    }
+
+    router.retain(|route| !route.starts_with("/files") && *route != "/users/:id/posts");
+
+    assert_eq!(router.at("/").map(|m| *m.value), Ok("/"));
+    assert_eq!(router.at("/users/1").map(|m| *m.value), Ok("/users/:id"));
+    assert_eq!(router.at("/users/new").map(|m| *m.value), Ok("/users/new"));
+    assert_eq!(
+        router.at("/users/1/posts").map(|m| *m.value),
+        Err(MatchError::NotFound)
+    );
+    assert_eq!(
+        router.at("/files/a").map(|m| *m.value),
+        Err(MatchError::NotFound)
+    );
+
+    if let Err((got, expected)) = router.check_priorities() {
+        panic!("priority mismatch: got '{}', expected '{}'", got, expected)
+    }
+
+    // removed routes don't conflict anymore
+    router.insert("/files/:file", "/files/:file").unwrap();
+    router
+        .insert("/users/:id/*rest", "/users/:id/*rest")
+        .unwrap();
+    assert_eq!(router.at("/files/a").map(|m| *m.value), Ok("/files/:file"));
+    assert_eq!(
+        router.at("/users/1/posts").map(|m| *m.value),
+        Ok("/users/:id/*rest")
+    );
+
+    router.retain(|_| false);
+    assert_eq!(router.at("/").map(|m| *m.value), Err(MatchError::NotFound));
+    router.insert("/", "/").unwrap();
+    assert_eq!(router.at("/").map(|m| *m.value), Ok("/"));
+}
+
+#[test]
+fn retain_compacts() {
+    let routes = [
+        "/",
+        "/ab",
+        "/abc",
+        "/users/new",
+        "/users/:id",
+        "/users/:id/posts",
+        "/x/1",
+        "/x/2",
+        "/x/3",
+        "/y",
+    ];
+    let removed = ["/ab", "/users/new", "/y"];
+
+    let mut router = Router::new();
+    for (i, route) in routes.iter().enumerate() {
+        router.insert(*route, i).unwrap();
+    }
+    router.retain(|&i| !removed.contains(&routes[i]));
+
+    // the tree is the same as if the removed routes were never inserted
+    let mut expected = Router::new();
+    for (i, route) in routes.iter().enumerate() {
+        if !removed.contains(route) {
+            expected.insert(*route, i).unwrap();
+        }
+    }
+
+    assert_eq!(router.to_static_table(), expected.to_static_table());
+
+    if let Err((got, expected)) = router.check_priorities() {
+        panic!("priority mismatch: got '{}', expected '{}'", got, expected)
+    }
+}
+
+#[test]
+fn static_routes() {
+    let mut router = Router::new();
+    for i in 0..100 {
+        router.insert(format!("/static/{}", i), i).unwrap();
+        // restructure the tree around the existing static routes
+        router
+            .insert(format!("/static/{}/:id", i), i + 1000)
+            .unwrap();
+    }
+
+    for i in 0..100 {
+        assert_eq!(
+            router.at(&format!("/static/{}", i)).map(|m| *m.value),
+            Ok(i)
+        );
+        assert_eq!(
+            router.at(&format!("/static/{}/x", i)).map(|m| *m.value),
+            Ok(i + 1000)
+        );
+    }
+
+    *router.at_mut("/static/1").unwrap().value = 1;
+    *router.at_mut("/static/2").unwrap().value += 1;
+    assert_eq!(router.at("/static/2").map(|m| *m.value), Ok(3));
+
+    let cloned = router.clone();
+    router.retain(|&value| value % 2 == 0);
+    assert_eq!(
+        router.at("/static/2").map(|m| *m.value),
+        Err(MatchError::NotFound)
+    );
+    assert_eq!(router.at("/static/4").map(|m| *m.value), Ok(4));
+    assert_eq!(cloned.at("/static/2").map(|m| *m.value), Ok(3));
+
+    let mapped = cloned.map(|value| value * 2);
+    assert_eq!(mapped.at("/static/2").map(|m| *m.value), Ok(6));
+    assert_eq!(mapped.at("/static/5/x").map(|m| *m.value), Ok(2010));
+}
+
 insert_tests! {
     wildcard_conflict {
         "/cmd/:tool/:sub"     => Ok(()),
@@ -467,9 +939,9 @@ insert_tests! {
         "/src/*"  => Err(InsertError::UnnamedParam),
     },
     double_params {
-        "/:foo:bar"  => Err(InsertError::TooManyParams),
-        "/:foo:bar/" => Err(InsertError::TooManyParams),
-        "/:foo*bar/" => Err(InsertError::TooManyParams),
+        "/:foo:bar"  => Err(InsertError::TooManySegmentParams),
+        "/:foo:bar/" => Err(InsertError::TooManySegmentParams),
+        "/:foo*bar/" => Err(InsertError::TooManySegmentParams),
     },
     normalized_conflict {
         "/x/:foo/bar"  => Ok(()),
@@ -520,6 +992,132 @@ insert_tests! {
         "/hey/user" => Ok(()),
         "/hey/user" => Err(InsertError::Conflict { with: "/hey/user".into() }),
     },
+    invalid_route {
+        ""              => Err(InsertError::InvalidRoute),
+        "users"         => Err(InsertError::InvalidRoute),
+        ":id"           => Err(InsertError::InvalidRoute),
+        "/hello world"  => Err(InsertError::InvalidRoute),
+        "/search?q"     => Err(InsertError::InvalidRoute),
+        "/page#top"     => Err(InsertError::InvalidRoute),
+        "/tab\there"    => Err(InsertError::InvalidRoute),
+        "/a{b}"         => Err(InsertError::InvalidRoute),
+        "/100%"         => Err(InsertError::InvalidRoute),
+        "/%zz"          => Err(InsertError::InvalidRoute),
+        "/%20"          => Ok(()),
+        "/~user/@me"    => Ok(()),
+        "/ʯ/:β"         => Ok(()),
+    },
+}
+
+#[test]
+fn validate() {
+    assert_eq!(Router::validate("/users/:id/*rest"), Ok(()));
+    assert_eq!(Router::validate("/users/:"), Err(InsertError::UnnamedParam));
+    assert_eq!(
+        Router::validate("/:a:b"),
+        Err(InsertError::TooManySegmentParams)
+    );
+    assert_eq!(Router::validate("/*a/b"), Err(InsertError::InvalidCatchAll));
+    assert_eq!(Router::validate("/a b"), Err(InsertError::InvalidRoute));
+
+    // validating doesn't care about conflicts
+    let mut router = Router::new();
+    router.insert("/users/:id", ()).unwrap();
+    assert_eq!(Router::validate("/users/:name"), Ok(()));
+}
+
+#[test]
+fn too_many_params() {
+    let route = |n: usize| (0..n).map(|i| format!("/:p{}", i)).collect::<String>();
+
+    assert_eq!(Router::validate(&route(26)), Ok(()));
+    assert_eq!(
+        Router::validate(&route(27)),
+        Err(InsertError::TooManyParams)
+    );
+    assert_eq!(Router::validate(&(route(26) + "/*rest")), Ok(()));
+
+    let mut router = Router::new();
+    router.insert(route(26), 26).unwrap();
+    let matched = router.at("/0/1/2/3/4/5/6/7/8/9/10/11/12/13/14/15/16/17/18/19/20/21/22/23/24/25");
+    assert_eq!(
+        matched.as_ref().map(|m| m.params.get("p25")),
+        Ok(Some("25"))
+    );
+
+    // the limit is enforced even without validation
+    let mut router = Router::new();
+    router.set_validation(false);
+    assert_eq!(
+        router.insert(route(27), 27),
+        Err(InsertError::TooManyParams)
+    );
+}
+
+#[test]
+fn disable_validation() {
+    let mut router = Router::new();
+    router.set_validation(false);
+    router.insert("/search?q", 1).unwrap();
+    router.insert("/hello world", 2).unwrap();
+
+    assert_eq!(router.at("/search?q").map(|m| *m.value), Ok(1));
+    assert_eq!(router.at("/hello world").map(|m| *m.value), Ok(2));
+}
+
+#[test]
+fn const_new() {
+    const ROUTER: Router<u32> = Router::new();
+
+    let mut router = ROUTER;
+    router.insert("/home", 1).unwrap();
+    assert_eq!(router.at("/home").map(|m| *m.value), Ok(1));
+    assert_eq!(
+        ROUTER.at("/home").map(|m| *m.value),
+        Err(MatchError::NotFound)
+    );
+}
+
+#[test]
+fn to_static_table() {
+    let mut router = Router::new();
+    router.insert("/:id", 0).unwrap();
+
+    assert_eq!(
+        router.to_static_table(),
+        "::matchit::__private::v1::node(b\"/\", b\"\", 0, true, \
+         ::core::option::Option::None, &[], &[\
+         ::matchit::__private::v1::node(b\":a\", b\"\", 1, false, \
+         ::core::option::Option::Some(0), &[b\"id\", ], &[]), ])"
+    );
+
+    // non-ascii bytes and quotes are escaped
+    let mut router = Router::new();
+    router.set_validation(false);
+    router.insert("/ü\"", 0).unwrap();
+    assert!(router
+        .to_static_table()
+        .starts_with(r#"::matchit::__private::v1::node(b"/\xc3\xbc\"", "#));
+}
+
+#[test]
+#[should_panic(expected = "invalid table")]
+fn invalid_static_table() {
+    use matchit::__private::v1::node;
+    use matchit::{StaticNode, StaticRouter};
+
+    // a parameter starting in the middle of a character
+    static ROOT: StaticNode = node(
+        b"/\xc3",
+        b"",
+        0,
+        true,
+        None,
+        &[],
+        &[node(b":id", b"", 1, false, Some(0), &[b"id"], &[])],
+    );
+
+    StaticRouter::from_table(&ROOT, [()]);
 }
 
 tsr_tests! {
@@ -660,6 +1258,34 @@ macro_rules! match_tests {
                     .unwrap_or_else(|e| panic!("error when inserting route '{}': {:?}", route, e));
             }
 
+            let frozen = router.clone().freeze();
+            let mut scratch = MatchScratch::new();
+
+            let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&router.clone().into_archivable()).unwrap();
+            let archived = rkyv::access::<ArchivedRouter<String>, rkyv::rancor::Error>(&bytes).unwrap();
+            $(
+                assert_eq!(
+                    archived.at($path).map(|m| (m.value.to_string(), m.params.iter().collect::<Vec<_>>())),
+                    router.at($path).map(|m| (m.value.clone(), m.params.iter().collect::<Vec<_>>())),
+                    "archived router differs for '{}'",
+                    $path
+                );
+
+                assert_eq!(
+                    frozen.at($path).map(|m| (m.value.clone(), m.params.iter().collect::<Vec<_>>())),
+                    router.at($path).map(|m| (m.value.clone(), m.params.iter().collect::<Vec<_>>())),
+                    "frozen router differs for '{}'",
+                    $path
+                );
+
+                assert_eq!(
+                    router.at_with(&mut scratch, $path).map(|m| (m.value.clone(), m.params.iter().collect::<Vec<_>>())),
+                    router.at($path).map(|m| (m.value.clone(), m.params.iter().collect::<Vec<_>>())),
+                    "scratch match differs for '{}'",
+                    $path
+                );
+            )*
+
             $(match router.at($path) {
                 Err(_) => {
                     $($( @$some )?
@@ -667,6 +1293,9 @@ macro_rules! match_tests {
                     )?
                 }
                 Ok(result) => {
+                    let first = router.matches($path).next().map(|m| m.value);
+                    assert_eq!(first, Some(result.value), "first match for '{}' differs", $path);
+
                     $($( @$some )?
                         if result.value != $route {
                             panic!(
@@ -741,7 +1370,33 @@ macro_rules! tsr_tests {
                     .unwrap_or_else(|e| panic!("error when inserting route '{}': {:?}", route, e));
             }
 
+            let frozen = router.clone().freeze();
+
+            let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&router.clone().into_archivable()).unwrap();
+            let archived = rkyv::access::<ArchivedRouter<String>, rkyv::rancor::Error>(&bytes).unwrap();
+
             $(
+                assert_eq!(
+                    archived.at($path).map(|m| m.value.to_string()),
+                    router.at($path).map(|m| m.value.clone()),
+                    "archived router differs for '{}'",
+                    $path
+                );
+
+                assert_eq!(
+                    frozen.at($path).map(|m| m.value.clone()),
+                    router.at($path).map(|m| m.value.clone()),
+                    "frozen router differs for '{}'",
+                    $path
+                );
+
+                assert_eq!(
+                    router.at_with(&mut MatchScratch::new(), $path).map(|m| m.value.clone()),
+                    router.at($path).map(|m| m.value.clone()),
+                    "scratch match differs for '{}'",
+                    $path
+                );
+
                 match router.at($path) {
                     Err(MatchError::$tsr) => {},
                     Err(e) => panic!("wrong tsr value for '{}', expected {}, found {}", $path, MatchError::$tsr, e),
@@ -752,4 +1407,101 @@ macro_rules! tsr_tests {
    )* };
 }
 
-pub(self) use {insert_tests, match_tests, tsr_tests};
+use {insert_tests, match_tests, tsr_tests};
+
+#[test]
+fn at_bytes() {
+    let mut router = Router::new();
+    router.insert("/files/:dir/*path", "files").unwrap();
+    router.insert("/users/:id", "users").unwrap();
+    router.insert("/caf\u{e9}", "cafe").unwrap();
+
+    let matched = router.at_bytes(b"/files/\xff\xfe/a/\xc3").unwrap();
+    assert_eq!(*matched.value, "files");
+    assert_eq!(matched.params.get("dir"), Some(&b"\xff\xfe"[..]));
+    assert_eq!(matched.params.get("path"), Some(&b"a/\xc3"[..]));
+    assert_eq!(matched.params.len(), 2);
+    assert_eq!(
+        matched.params.iter().collect::<Vec<_>>(),
+        [("dir", &b"\xff\xfe"[..]), ("path", &b"a/\xc3"[..])]
+    );
+
+    let matched = router.at_bytes("/users/\u{e9}".as_bytes()).unwrap();
+    assert_eq!(matched.params.get("id"), Some("\u{e9}".as_bytes()));
+
+    assert_eq!(
+        router.at_bytes("/caf\u{e9}".as_bytes()).map(|m| *m.value),
+        Ok("cafe")
+    );
+    assert_eq!(
+        router.at_bytes(b"/caf\xc3").err(),
+        Some(MatchError::NotFound)
+    );
+    assert_eq!(
+        router.at_bytes(b"/users/1/").err(),
+        Some(MatchError::ExtraTrailingSlash)
+    );
+}
+
+#[test]
+fn meta() {
+    #[derive(Clone, Debug, Default, PartialEq)]
+    struct Meta {
+        auth: bool,
+        operation: &'static str,
+    }
+
+    let user = Meta {
+        auth: true,
+        operation: "getUser",
+    };
+
+    let mut router = Router::with_meta();
+    router.insert("/health", "health").unwrap();
+    router
+        .insert_with_meta("/users/:id", "user", user.clone())
+        .unwrap();
+    router
+        .insert_with_meta(
+            "/users/new",
+            "new user",
+            Meta {
+                auth: true,
+                operation: "newUser",
+            },
+        )
+        .unwrap();
+
+    let (matched, meta) = router.at_meta("/users/1").unwrap();
+    assert_eq!((*matched.value, meta), ("user", &user));
+    assert_eq!(matched.params.get("id"), Some("1"));
+
+    // static routes are matched without walking the tree
+    assert_eq!(router.at_meta("/users/new").unwrap().1.operation, "newUser");
+    assert_eq!(router.at_meta("/health").unwrap().1, &Meta::default());
+
+    *router.at_mut("/users/2").unwrap().value = "updated";
+    let (matched, meta) = router.at_meta("/users/1").unwrap();
+    assert_eq!((*matched.value, meta), ("updated", &user));
+
+    let mut routes = router
+        .routes()
+        .map(|(route, value, meta)| (route, *value, meta.operation))
+        .collect::<Vec<_>>();
+    routes.sort();
+    assert_eq!(
+        routes,
+        [
+            ("/health".to_owned(), "health", ""),
+            ("/users/:id".to_owned(), "updated", "getUser"),
+            ("/users/new".to_owned(), "new user", "newUser"),
+        ]
+    );
+
+    // metadata is kept when values are transformed or routes are moved
+    let router = router.map(str::len);
+    let mut api = Router::with_meta();
+    api.nest("/api", router).unwrap();
+    let (matched, meta) = api.at_meta("/api/users/1").unwrap();
+    assert_eq!((*matched.value, meta), (7, &user));
+}
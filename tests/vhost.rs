@@ -0,0 +1,225 @@
+use matchit::{InsertError, MatchError, VirtualRouter};
+
+fn router() -> VirtualRouter<&'static str> {
+    let mut router = VirtualRouter::new();
+
+    for route in [
+        "api.example.com/users/:id",
+        "api.example.com/users/:id/posts/",
+        ":tenant.example.com/",
+        ":tenant.example.com/users/:id",
+        "*sub.cdn.example.com/*path",
+        "/",
+        "/health",
+        "/users/:name",
+        "/about/",
+    ] {
+        router
+            .insert(route, route)
+            .unwrap_or_else(|e| panic!("error when inserting route '{}': {:?}", route, e));
+    }
+
+    router
+}
+
+#[test]
+fn hosts() {
+    let router = router();
+
+    let matched = router.at("api.example.com", "/users/1").unwrap();
+    assert_eq!(*matched.value, "api.example.com/users/:id");
+    assert_eq!(matched.params.get("id"), Some("1"));
+
+    let matched = router.at("acme.example.com", "/users/2").unwrap();
+    assert_eq!(*matched.value, ":tenant.example.com/users/:id");
+    let params = matched.params.iter().collect::<Vec<_>>();
+    assert_eq!(params, [("tenant", "acme"), ("id", "2")]);
+
+    let matched = router.at("eu.img.cdn.example.com", "/a/b.png").unwrap();
+    let params = matched.params.iter().collect::<Vec<_>>();
+    assert_eq!(params, [("sub", "eu.img"), ("path", "a/b.png")]);
+
+    // hosts are matched case-insensitively
+    assert_eq!(
+        router.at("API.example.com.", "/users/1").map(|m| *m.value),
+        Ok("api.example.com/users/:id")
+    );
+}
+
+#[test]
+fn fallback() {
+    let router = router();
+
+    // the host matches, but none of its paths do
+    let matched = router.at("api.example.com", "/health").unwrap();
+    assert_eq!(*matched.value, "/health");
+    assert!(matched.params.is_empty());
+
+    // an unknown host
+    let matched = router.at("example.org", "/users/bob").unwrap();
+    assert_eq!(*matched.value, "/users/:name");
+    assert_eq!(matched.params.get("name"), Some("bob"));
+
+    // routes of the host take precedence
+    assert_eq!(
+        router.at("acme.example.com", "/").map(|m| *m.value),
+        Ok(":tenant.example.com/")
+    );
+    assert_eq!(router.at("example.org", "/").map(|m| *m.value), Ok("/"));
+
+    assert_eq!(
+        router.at("example.org", "/missing").err(),
+        Some(MatchError::NotFound)
+    );
+}
+
+#[test]
+fn trailing_slash() {
+    let router = router();
+
+    // recommendations of the host
+    assert_eq!(
+        router.at("api.example.com", "/users/1/posts").err(),
+        Some(MatchError::MissingTrailingSlash)
+    );
+
+    // recommendations of the fallback
+    assert_eq!(
+        router.at("api.example.com", "/about").err(),
+        Some(MatchError::MissingTrailingSlash)
+    );
+    assert_eq!(
+        router.at("example.org", "/health/").err(),
+        Some(MatchError::ExtraTrailingSlash)
+    );
+}
+
+#[test]
+fn invalid_routes() {
+    let mut router = router();
+
+    assert_eq!(
+        router.insert("example.com", ""),
+        Err(InsertError::InvalidRoute)
+    );
+    assert_eq!(
+        router.insert("exa mple.com/", ""),
+        Err(InsertError::InvalidRoute)
+    );
+    assert_eq!(
+        router.insert("example.com/*a/b", ""),
+        Err(InsertError::InvalidCatchAll)
+    );
+
+    // the host wasn't inserted
+    assert_eq!(
+        router.at("example.com", "/x").err(),
+        Some(MatchError::NotFound)
+    );
+}
+
+#[test]
+fn conflicts() {
+    let mut router = router();
+
+    assert_eq!(
+        router.insert("api.example.com/users/:user_id", ""),
+        Err(InsertError::Conflict {
+            with: "api.example.com/users/:id".to_owned()
+        })
+    );
+    assert_eq!(
+        router.insert(":name.example.com/", ""),
+        Err(InsertError::Conflict {
+            with: ":tenant.example.com".to_owned()
+        })
+    );
+    assert_eq!(
+        router.insert("/users/:id", ""),
+        Err(InsertError::Conflict {
+            with: "/users/:name".to_owned()
+        })
+    );
+
+    // the same route for another host
+    router.insert("www.example.com/users/:id", "").unwrap();
+}
+
+#[test]
+fn at_mut() {
+    let mut router = VirtualRouter::new();
+    router.insert(":tenant.example.com/", 0).unwrap();
+    router.insert("/", 10).unwrap();
+
+    *router.at_mut("acme.example.com", "/").unwrap().value += 1;
+    *router.at_mut("example.org", "/").unwrap().value += 1;
+
+    assert_eq!(router.at("other.example.com", "/").map(|m| *m.value), Ok(1));
+    assert_eq!(router.at("localhost", "/").map(|m| *m.value), Ok(11));
+}
+
+#[test]
+fn unnamed_catch_all_host() {
+    let mut router = VirtualRouter::new();
+    router.insert("*.example.com/a", "a").unwrap();
+    router.insert("*.example.com/b/:id", "b").unwrap();
+
+    let matched = router.at("x.y.example.com", "/b/1").unwrap();
+    assert_eq!(*matched.value, "b");
+    assert_eq!(matched.params.iter().collect::<Vec<_>>(), [("id", "1")]);
+    assert_eq!(router.at("x.example.com", "/a").map(|m| *m.value), Ok("a"));
+
+    // a named catch-all is a different route for the same hosts
+    assert_eq!(
+        router.insert("*sub.example.com/c", "c"),
+        Err(InsertError::Conflict {
+            with: "*.example.com".to_owned()
+        })
+    );
+}
+
+#[test]
+fn distinct_hosts() {
+    let mut router = VirtualRouter::new();
+    router.insert("*a.example.com/x", 1).unwrap();
+
+    // a catch-all matches the text of another host, but is a different route
+    assert_eq!(
+        router.insert(":a.example.com/y", 2),
+        Err(InsertError::Conflict {
+            with: "*a.example.com".to_owned()
+        })
+    );
+    assert_eq!(
+        router.at("x.y.example.com", "/y").err(),
+        Some(MatchError::NotFound)
+    );
+}
+
+#[test]
+fn every_matching_host() {
+    let mut router = VirtualRouter::new();
+    router.insert("api.example.com/x", "x").unwrap();
+    router.insert(":tenant.example.com/users", "users").unwrap();
+    router.insert("/users/", "any").unwrap();
+
+    // the static host doesn't have the path, but a parameterized one does
+    let matched = router.at("api.example.com", "/users").unwrap();
+    assert_eq!(*matched.value, "users");
+    assert_eq!(matched.params.get("tenant"), Some("api"));
+
+    assert_eq!(
+        router.at("api.example.com", "/x").map(|m| *m.value),
+        Ok("x")
+    );
+
+    // routes without a host win over trailing slash recommendations of the hosts
+    assert_eq!(
+        router.at("api.example.com", "/users/").map(|m| *m.value),
+        Ok("any")
+    );
+    assert_eq!(
+        router.at("api.example.com", "/x/").err(),
+        Some(MatchError::ExtraTrailingSlash)
+    );
+}